@@ -0,0 +1,25 @@
+use std::io::BufRead;
+
+use google_genai::datatypes::{Content, GenerateContentReq};
+
+#[tokio::main]
+async fn main() -> google_genai::error::Result<()> {
+    let api_key = std::env::var("GOOGLEAI_API_KEY")
+        .expect("GOOGLEAI_API_KEY environment variable must be set");
+
+    let client = google_genai::Client::new(api_key)?;
+    let mut chat = client.start_chat(
+        GenerateContentReq::default()
+            .model("gemini-2.0-flash")
+            .system_instruction(Content::user_text("Answer in one sentence.")),
+    )?;
+
+    for line in std::io::stdin().lock().lines() {
+        let line = line.expect("failed to read stdin");
+        let response = chat.send(Content::user_text(line)).await?;
+        println!("{}", response.text().unwrap_or_default());
+    }
+    println!("{} turns", chat.history().len() / 2);
+
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use futures_util::TryStreamExt;
+
+#[tokio::main]
+async fn main() -> google_genai::error::Result<()> {
+    let api_key = std::env::var("GOOGLEAI_API_KEY")
+        .expect("GOOGLEAI_API_KEY environment variable must be set");
+
+    let client = google_genai::Client::new(api_key)?;
+    let mut models = client.list_models(None);
+    while let Some(model) = models.try_next().await? {
+        if model.supports("generateContent") {
+            println!(
+                "{} (input: {:?}, output: {:?})",
+                model.name.unwrap_or_default(),
+                model.input_token_limit,
+                model.output_token_limit
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use google_genai::datatypes::{Content, GenerateContentParameters, Part};
+use google_genai::datatypes::{Content, GenerateContentParameters, GenerateContentReq, Part};
 
 #[tokio::main]
 async fn main() -> google_genai::error::Result<()> {
@@ -14,11 +14,10 @@ async fn main() -> google_genai::error::Result<()> {
         }])
         .model("gemini-exp-1206");
 
-    let request = google_genai::datatypes::GenerateContentReq::default()
-        .contents(params.contents.unwrap())
-        .model(params.model.unwrap());
+    let request = GenerateContentReq::try_from(params)?;
 
-    let response = google_genai::generate_content(&api_key, request).await?;
+    let client = google_genai::Client::new(api_key)?;
+    let response = client.generate_content(request).await?;
 
     println!("Response: {:#?}", response);
 
@@ -1,46 +1,35 @@
 use futures_util::StreamExt;
-use google_genai::datatypes::{Content, GenerateContentParameters, Part};
+use google_genai::datatypes::{Content, GenerateContentParameters, GenerateContentReq, Part};
+use google_genai::TextDeltaStream;
 use std::env;
 
 #[tokio::main]
 async fn main() -> google_genai::error::Result<()> {
     let api_key = env::var("GOOGLEAI_API_KEY").expect("GOOGLE_API_KEY must be set");
 
-    let params =
-        GenerateContentParameters::default().contents(vec![Content::default().parts(vec![
-            Part::default().text("Write a story about a magic backpack."),
-        ])]);
+    let params = GenerateContentParameters::default()
+        .contents(vec![Content::default().parts(vec![
+            Part::default().text("Write a story about a magic backpack.")
+        ])])
+        .model("gemini-2.0-flash");
 
-    let request = google_genai::datatypes::GenerateContentReq::default()
-        .contents(params.contents.unwrap())
-        .model(params.model.unwrap());
+    let request = GenerateContentReq::try_from(params)?;
 
-    let mut stream = google_genai::generate_content_stream(&api_key, request).await?;
+    let client = google_genai::Client::new(api_key)?;
+    let mut stream = TextDeltaStream::new(client.generate_content_stream(request).await?);
 
-    while let Some(response) = stream.next().await {
-        match response {
-            Ok(content) => {
-                if content.candidates.is_none() {
-                    // This is our [DONE] marker
-                    break;
-                }
-                if let Some(candidates) = content.candidates {
-                    for candidate in candidates {
-                        if let Some(content) = candidate.content {
-                            if let Some(parts) = content.parts {
-                                for part in parts {
-                                    if let Some(text) = part.text {
-                                        print!("{}", text);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+    while let Some(text) = stream.next().await {
+        match text {
+            Ok(text) => print!("{}", text),
             Err(e) => eprintln!("Error: {}", e),
         }
     }
 
+    let response = stream.finish();
+    println!("\n\nFinish reason: {:?}", response.finish_reason());
+    if let Some(usage) = response.usage_metadata {
+        println!("Total tokens: {:?}", usage.total_token_count);
+    }
+
     Ok(())
 }
@@ -0,0 +1,56 @@
+use google_genai::datatypes::{Content, GenerateContentReq};
+use google_genai::{tool, GenAiSchema, ToolRegistry};
+use serde::{Deserialize, Serialize};
+
+/// A temperature unit.
+#[derive(Debug, Deserialize, Serialize, GenAiSchema)]
+#[serde(rename_all = "lowercase")]
+enum Unit {
+    Celsius,
+    Fahrenheit,
+}
+
+/// Current weather conditions.
+#[derive(Debug, Serialize)]
+struct Weather {
+    temperature: f64,
+    unit: Unit,
+    conditions: String,
+}
+
+/// Returns the current weather for a city.
+#[tool]
+async fn get_weather(
+    /// The name of the city.
+    city: String,
+    /// Defaults to celsius.
+    unit: Option<Unit>,
+) -> Result<Weather, String> {
+    if city.is_empty() {
+        return Err("No city given".to_string());
+    }
+    Ok(Weather {
+        temperature: 21.0,
+        unit: unit.unwrap_or(Unit::Celsius),
+        conditions: "sunny".to_string(),
+    })
+}
+
+#[tokio::main]
+async fn main() -> google_genai::error::Result<()> {
+    let api_key = std::env::var("GOOGLEAI_API_KEY")
+        .expect("GOOGLEAI_API_KEY environment variable must be set");
+
+    let client = google_genai::Client::new(api_key)?;
+    let registry = ToolRegistry::new().register(get_weather::declaration(), get_weather::call);
+    let req = GenerateContentReq::default()
+        .model("gemini-2.0-flash")
+        .contents(vec![Content::user_text(
+            "Should I bring a jacket in Paris and in Chicago today?",
+        )]);
+
+    let result = client.generate_content_with_tools(req, &registry).await?;
+    println!("{}", result.response.text().unwrap_or_default());
+
+    Ok(())
+}
@@ -0,0 +1,207 @@
+//! Parsing of doc comments and serde attributes.
+
+use syn::{parenthesized, token, Attribute, Expr, ExprLit, Lit, LitStr, Meta, Result, Token};
+
+/// Joins the `///` doc comments of an item into a description.
+pub fn docs(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .filter_map(|a| match &a.meta {
+            Meta::NameValue(nv) => match &nv.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(s), ..
+                }) => Some(s.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| {
+            line.strip_prefix(' ')
+                .unwrap_or(&line)
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+    let doc = lines.join("\n").trim().to_string();
+    (!doc.is_empty()).then_some(doc)
+}
+
+/// Container-level serde attributes.
+#[derive(Default)]
+pub struct ContainerAttrs {
+    pub rename_all: Option<RenameRule>,
+    pub default: bool,
+    pub transparent: bool,
+}
+
+/// Field- and variant-level serde attributes.
+#[derive(Default)]
+pub struct MemberAttrs {
+    pub rename: Option<String>,
+    pub skip: bool,
+    pub default: bool,
+    pub other: bool,
+}
+
+impl ContainerAttrs {
+    pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut out = Self::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename_all") {
+                    out.rename_all = Some(RenameRule::parse(&meta.value()?.parse()?)?);
+                } else if meta.path.is_ident("default") {
+                    out.default = true;
+                    skip_value(&meta)?;
+                } else if meta.path.is_ident("transparent") {
+                    out.transparent = true;
+                } else if meta.path.is_ident("tag")
+                    || meta.path.is_ident("untagged")
+                    || meta.path.is_ident("content")
+                {
+                    return Err(meta.error("GenAiSchema does not support tagged enums"));
+                } else {
+                    skip_value(&meta)?;
+                }
+                Ok(())
+            })?;
+        }
+        Ok(out)
+    }
+}
+
+impl MemberAttrs {
+    pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut out = Self::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    if meta.input.peek(Token![=]) {
+                        out.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                    } else {
+                        // `rename(serialize = "..", deserialize = "..")`: the model produces
+                        // values that are deserialized, so the deserialize name wins.
+                        meta.parse_nested_meta(|inner| {
+                            let name = inner.value()?.parse::<LitStr>()?.value();
+                            if inner.path.is_ident("deserialize") {
+                                out.rename = Some(name);
+                            }
+                            Ok(())
+                        })?;
+                    }
+                } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_deserializing") {
+                    out.skip = true;
+                } else if meta.path.is_ident("default") {
+                    out.default = true;
+                    skip_value(&meta)?;
+                } else if meta.path.is_ident("other") {
+                    out.other = true;
+                } else if meta.path.is_ident("flatten") {
+                    return Err(meta.error("GenAiSchema does not support flattened fields"));
+                } else {
+                    skip_value(&meta)?;
+                }
+                Ok(())
+            })?;
+        }
+        Ok(out)
+    }
+}
+
+/// Consumes the value of an attribute we don't interpret.
+fn skip_value(meta: &syn::meta::ParseNestedMeta) -> Result<()> {
+    if meta.input.peek(Token![=]) {
+        meta.value()?.parse::<Expr>()?;
+    } else if meta.input.peek(token::Paren) {
+        let content;
+        parenthesized!(content in meta.input);
+        content.parse::<proc_macro2::TokenStream>()?;
+    }
+    Ok(())
+}
+
+/// A serde `rename_all` case convention.
+#[derive(Clone, Copy)]
+pub enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn parse(lit: &LitStr) -> Result<Self> {
+        Ok(match lit.value().as_str() {
+            "lowercase" => Self::Lower,
+            "UPPERCASE" => Self::Upper,
+            "PascalCase" => Self::Pascal,
+            "camelCase" => Self::Camel,
+            "snake_case" => Self::Snake,
+            "SCREAMING_SNAKE_CASE" => Self::ScreamingSnake,
+            "kebab-case" => Self::Kebab,
+            "SCREAMING-KEBAB-CASE" => Self::ScreamingKebab,
+            _ => return Err(syn::Error::new(lit.span(), "unknown rename rule")),
+        })
+    }
+
+    /// Renames a `snake_case` field, as serde does.
+    pub fn field(self, name: &str) -> String {
+        match self {
+            Self::Lower | Self::Snake => name.to_string(),
+            Self::Upper | Self::ScreamingSnake => name.to_ascii_uppercase(),
+            Self::Pascal | Self::Camel => {
+                let mut out = String::new();
+                let mut capitalize = matches!(self, Self::Pascal);
+                for c in name.chars() {
+                    if c == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        out.push(c.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        out.push(c);
+                    }
+                }
+                out
+            }
+            Self::Kebab => name.replace('_', "-"),
+            Self::ScreamingKebab => name.replace('_', "-").to_ascii_uppercase(),
+        }
+    }
+
+    /// Renames a `PascalCase` variant, as serde does.
+    pub fn variant(self, name: &str) -> String {
+        match self {
+            Self::Lower => name.to_ascii_lowercase(),
+            Self::Upper => name.to_ascii_uppercase(),
+            Self::Pascal => name.to_string(),
+            Self::Camel => {
+                let mut chars = name.chars();
+                chars
+                    .next()
+                    .map(|c| c.to_ascii_lowercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            }
+            _ => {
+                let mut snake = String::new();
+                for (i, c) in name.chars().enumerate() {
+                    if i > 0 && c.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(c.to_ascii_lowercase());
+                }
+                match self {
+                    Self::ScreamingSnake => snake.to_ascii_uppercase(),
+                    Self::Kebab => snake.replace('_', "-"),
+                    Self::ScreamingKebab => snake.replace('_', "-").to_ascii_uppercase(),
+                    _ => snake,
+                }
+            }
+        }
+    }
+}
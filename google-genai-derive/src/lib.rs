@@ -0,0 +1,45 @@
+//! Derive macros for the `google-genai` crate. Use them through the re-exports in
+//! `google_genai::schema`.
+
+mod attrs;
+mod schema;
+mod tool;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput, ItemFn};
+
+/// Implements `GenAiSchema` for a struct or an enum of unit variants.
+///
+/// Doc comments become descriptions, `Option` fields are nullable and not required, and
+/// serde's `rename`, `rename_all`, `skip` and `default` attributes are respected.
+#[proc_macro_derive(GenAiSchema, attributes(serde))]
+pub fn derive_gen_ai_schema(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    schema::expand(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Exposes a function to the model.
+///
+/// Generates a module with the same name as the function, containing `declaration()`, which
+/// returns its `FunctionDeclaration`, and `call(args)`, which deserializes the arguments of
+/// a `FunctionCall`, runs the function and serializes its result. Every argument type must
+/// implement `GenAiSchema` and `Deserialize`, and the return type must implement
+/// `Serialize`, or be a `Result` of such a type whose error implements `Display`.
+///
+/// The function's doc comment becomes the description, and doc comments on arguments
+/// describe the parameters. The name and description can be overridden with
+/// `#[tool(name = "...", description = "...")]`.
+#[proc_macro_attribute]
+pub fn tool(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut tool_args = tool::ToolArgs::default();
+    {
+        let parser = tool_args.parser();
+        parse_macro_input!(args with parser);
+    }
+    let item = parse_macro_input!(item as ItemFn);
+    tool::expand(tool_args, item)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
@@ -0,0 +1,243 @@
+//! `#[derive(GenAiSchema)]`.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::{parse_quote, Data, DeriveInput, Error, Fields, Result, Type};
+
+use crate::attrs::{docs, ContainerAttrs, MemberAttrs};
+
+pub fn expand(mut input: DeriveInput) -> Result<TokenStream> {
+    let container = ContainerAttrs::parse(&input.attrs)?;
+    let description = describe(docs(&input.attrs));
+    let body = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) if !container.transparent => {
+                let mut properties = Vec::new();
+                let mut names = Vec::new();
+                let mut required = Vec::new();
+                for field in &fields.named {
+                    let attrs = MemberAttrs::parse(&field.attrs)?;
+                    if attrs.skip {
+                        continue;
+                    }
+                    let ident = field.ident.as_ref().expect("named field").unraw();
+                    let name = match (attrs.rename, container.rename_all) {
+                        (Some(name), _) => name,
+                        (None, Some(rule)) => rule.field(&ident.to_string()),
+                        (None, None) => ident.to_string(),
+                    };
+                    if !attrs.default && !container.default && !is_option(&field.ty) {
+                        required.push(name.clone());
+                    }
+                    properties.push(property(&name, &field.ty, docs(&field.attrs)));
+                    names.push(name);
+                }
+                let required = (!required.is_empty())
+                    .then(|| quote!(.required(::std::vec![#(#required.to_string()),*])));
+                quote! {
+                    let mut properties = ::std::collections::HashMap::new();
+                    #(#properties)*
+                    ::google_genai::datatypes::Schema::new(::google_genai::datatypes::Type::Object)
+                        .properties(properties)
+                        .property_ordering(::std::vec![#(#names.to_string()),*])
+                        #required
+                        #description
+                }
+            }
+            Fields::Named(fields) if fields.named.len() == 1 => {
+                let ty = &fields.named[0].ty;
+                quote!(<#ty as ::google_genai::schema::GenAiSchema>::schema() #description)
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let ty = &fields.unnamed[0].ty;
+                quote!(<#ty as ::google_genai::schema::GenAiSchema>::schema() #description)
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "GenAiSchema requires named fields or a single unnamed field",
+                ))
+            }
+        },
+        Data::Enum(data) => {
+            let mut values = Vec::new();
+            for variant in &data.variants {
+                if !matches!(variant.fields, Fields::Unit) {
+                    return Err(Error::new_spanned(
+                        variant,
+                        "GenAiSchema only supports enums with unit variants",
+                    ));
+                }
+                let attrs = MemberAttrs::parse(&variant.attrs)?;
+                if attrs.skip || attrs.other {
+                    continue;
+                }
+                let ident = variant.ident.unraw().to_string();
+                values.push(match (attrs.rename, container.rename_all) {
+                    (Some(name), _) => name,
+                    (None, Some(rule)) => rule.variant(&ident),
+                    (None, None) => ident,
+                });
+            }
+            quote! {
+                ::google_genai::datatypes::Schema::new(::google_genai::datatypes::Type::String)
+                    .enum_values([#(#values),*])
+                    #description
+            }
+        }
+        Data::Union(_) => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "GenAiSchema does not support unions",
+            ))
+        }
+    };
+
+    let type_params: Vec<_> = input
+        .generics
+        .type_params()
+        .map(|p| p.ident.clone())
+        .collect();
+    let where_clause = input.generics.make_where_clause();
+    for param in type_params {
+        where_clause
+            .predicates
+            .push(parse_quote!(#param: ::google_genai::schema::GenAiSchema));
+    }
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::google_genai::schema::GenAiSchema for #ident #ty_generics #where_clause {
+            fn schema() -> ::google_genai::datatypes::Schema {
+                #body
+            }
+        }
+    })
+}
+
+/// Inserts the schema of a property into a `properties` map.
+pub fn property(name: &str, ty: &Type, doc: Option<String>) -> TokenStream {
+    let description = describe(doc);
+    quote! {
+        properties.insert(
+            #name.to_string(),
+            <#ty as ::google_genai::schema::GenAiSchema>::schema() #description,
+        );
+    }
+}
+
+/// A `.description(..)` call, if there is a description.
+fn describe(doc: Option<String>) -> Option<TokenStream> {
+    doc.map(|d| quote!(.description(#d)))
+}
+
+/// Whether a type is spelled as `Option<..>`, and so may be omitted.
+pub fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(p) => p.path.segments.last().is_some_and(|s| s.ident == "Option"),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(input: DeriveInput) -> String {
+        match expand(input) {
+            Ok(tokens) => panic!("expected an error, got {}", tokens),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn unsupported_types_are_rejected() {
+        assert_eq!(
+            error(parse_quote!(
+                struct Pair(u32, u32);
+            )),
+            "GenAiSchema requires named fields or a single unnamed field"
+        );
+        assert_eq!(
+            error(parse_quote!(
+                struct Unit;
+            )),
+            "GenAiSchema requires named fields or a single unnamed field"
+        );
+        assert_eq!(
+            error(parse_quote!(
+                union Bits {
+                    a: u32,
+                    b: f32,
+                }
+            )),
+            "GenAiSchema does not support unions"
+        );
+        assert_eq!(
+            error(parse_quote!(
+                enum Shape {
+                    Circle(f64),
+                }
+            )),
+            "GenAiSchema only supports enums with unit variants"
+        );
+    }
+
+    #[test]
+    fn unsupported_serde_attributes_are_rejected() {
+        assert_eq!(
+            error(parse_quote!(
+                #[serde(tag = "kind")]
+                enum Shape {
+                    Circle,
+                }
+            )),
+            "GenAiSchema does not support tagged enums"
+        );
+        assert_eq!(
+            error(parse_quote!(
+                #[serde(untagged)]
+                enum Shape {
+                    Circle,
+                }
+            )),
+            "GenAiSchema does not support tagged enums"
+        );
+        assert_eq!(
+            error(parse_quote!(
+                struct Outer {
+                    #[serde(flatten)]
+                    inner: Inner,
+                }
+            )),
+            "GenAiSchema does not support flattened fields"
+        );
+        assert_eq!(
+            error(parse_quote!(
+                #[serde(rename_all = "Title Case")]
+                struct Outer {
+                    a: u32,
+                }
+            )),
+            "unknown rename rule"
+        );
+    }
+
+    #[test]
+    fn other_serde_attributes_are_ignored() {
+        let input: DeriveInput = parse_quote!(
+            #[serde(
+                deny_unknown_fields,
+                rename_all = "camelCase",
+                bound(serialize = "T: Clone")
+            )]
+            struct Outer<T> {
+                #[serde(with = "helpers", skip_serializing_if = "Option::is_none")]
+                a: Option<T>,
+            }
+        );
+        let tokens = expand(input).unwrap().to_string();
+        assert!(tokens.contains("T : :: google_genai :: schema :: GenAiSchema"));
+    }
+}
@@ -0,0 +1,215 @@
+//! `#[tool]`.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::{Error, FnArg, ItemFn, LitStr, Pat, Result, ReturnType, Type};
+
+use crate::attrs::docs;
+use crate::schema::{is_option, property};
+
+/// Options given to the attribute, e.g. `#[tool(name = "lookup")]`.
+#[derive(Default)]
+pub struct ToolArgs {
+    name: Option<String>,
+    description: Option<String>,
+}
+
+impl ToolArgs {
+    pub fn parser(&mut self) -> impl syn::parse::Parser<Output = ()> + '_ {
+        syn::meta::parser(|meta| {
+            if meta.path.is_ident("name") {
+                self.name = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("description") {
+                self.description = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else {
+                return Err(meta.error("expected `name` or `description`"));
+            }
+            Ok(())
+        })
+    }
+}
+
+pub fn expand(args: ToolArgs, mut item: ItemFn) -> Result<TokenStream> {
+    // Doc comments aren't allowed on parameters, so they are used as descriptions and
+    // removed before the function is emitted.
+    let mut param_docs = Vec::new();
+    for input in item.sig.inputs.iter_mut() {
+        if let FnArg::Typed(arg) = input {
+            param_docs.push(docs(&arg.attrs));
+            arg.attrs.retain(|a| !a.path().is_ident("doc"));
+        }
+    }
+    let sig = &item.sig;
+    if !sig.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &sig.generics,
+            "#[tool] functions cannot be generic",
+        ));
+    }
+
+    let mut idents = Vec::new();
+    let mut names = Vec::new();
+    let mut properties = Vec::new();
+    let mut required = Vec::new();
+    let mut decode = Vec::new();
+    for (input, doc) in sig.inputs.iter().zip(param_docs) {
+        let FnArg::Typed(arg) = input else {
+            return Err(Error::new_spanned(
+                input,
+                "#[tool] functions cannot take self",
+            ));
+        };
+        let Pat::Ident(pat) = &*arg.pat else {
+            return Err(Error::new_spanned(
+                &arg.pat,
+                "#[tool] arguments must be plain identifiers",
+            ));
+        };
+        let ident = &pat.ident;
+        let ty = &arg.ty;
+        let name = ident.unraw().to_string();
+        if !is_option(ty) {
+            required.push(name.clone());
+        }
+        properties.push(property(&name, ty, doc));
+        names.push(name.clone());
+        decode.push(quote! {
+            let #ident: #ty = ::google_genai::__private::arg(&args, #name)?;
+        });
+        idents.push(ident);
+    }
+
+    let fn_ident = &sig.ident;
+    let name = args.name.unwrap_or_else(|| fn_ident.unraw().to_string());
+    let description = match args.description.or_else(|| docs(&item.attrs)) {
+        Some(d) => quote!(::std::option::Option::Some(#d.to_string())),
+        None => quote!(::std::option::Option::None),
+    };
+    let parameters = if idents.is_empty() {
+        quote!(::std::option::Option::None)
+    } else {
+        quote! {{
+            let mut properties = ::std::collections::HashMap::new();
+            #(#properties)*
+            ::std::option::Option::Some(
+                ::google_genai::datatypes::Schema::new(::google_genai::datatypes::Type::Object)
+                    .properties(properties)
+                    .property_ordering(::std::vec![#(#names.to_string()),*])
+                    .required(::std::vec![#(#required.to_string()),*]),
+            )
+        }}
+    };
+
+    let call = match sig.asyncness {
+        Some(_) => quote!(super::#fn_ident(#(#idents),*).await),
+        None => quote!(super::#fn_ident(#(#idents),*)),
+    };
+    let output = if returns_result(&sig.output) {
+        quote!(#call.map_err(::google_genai::__private::tool_error)?)
+    } else {
+        quote!(#call)
+    };
+
+    let vis = &item.vis;
+    let doc = format!("Function-calling support for [`{}()`].", fn_ident);
+    Ok(quote! {
+        #item
+
+        #[doc = #doc]
+        #vis mod #fn_ident {
+            #[allow(unused_imports)]
+            use super::*;
+
+            /// The declaration to pass to the model.
+            pub fn declaration() -> ::google_genai::datatypes::FunctionDeclaration {
+                ::google_genai::datatypes::FunctionDeclaration {
+                    name: #name.to_string(),
+                    description: #description,
+                    parameters: #parameters,
+                    response: ::std::option::Option::None,
+                }
+            }
+
+            /// Deserializes the arguments of a call, runs the function and serializes its
+            /// result.
+            pub async fn call(
+                args: ::google_genai::__private::serde_json::Value,
+            ) -> ::google_genai::error::Result<::google_genai::__private::serde_json::Value> {
+                #(#decode)*
+                let output = #output;
+                ::google_genai::__private::result(output)
+            }
+        }
+    })
+}
+
+/// Whether a return type is spelled as `Result<..>`.
+fn returns_result(output: &ReturnType) -> bool {
+    match output {
+        ReturnType::Type(_, ty) => match &**ty {
+            Type::Path(p) => p.path.segments.last().is_some_and(|s| s.ident == "Result"),
+            _ => false,
+        },
+        ReturnType::Default => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse::Parser;
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn error(item: ItemFn) -> String {
+        match expand(ToolArgs::default(), item) {
+            Ok(tokens) => panic!("expected an error, got {}", tokens),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn unsupported_functions_are_rejected() {
+        assert_eq!(
+            error(parse_quote!(
+                fn first<T>(items: Vec<T>) -> T {}
+            )),
+            "#[tool] functions cannot be generic"
+        );
+        assert_eq!(
+            error(parse_quote!(
+                fn get(&self, key: String) -> String {}
+            )),
+            "#[tool] functions cannot take self"
+        );
+        assert_eq!(
+            error(parse_quote!(
+                fn area((w, h): (f64, f64)) -> f64 {}
+            )),
+            "#[tool] arguments must be plain identifiers"
+        );
+    }
+
+    #[test]
+    fn attribute_arguments() {
+        let mut args = ToolArgs::default();
+        args.parser()
+            .parse2(quote!(name = "lookup", description = "Finds things."))
+            .unwrap();
+        assert_eq!(args.name.as_deref(), Some("lookup"));
+        assert_eq!(args.description.as_deref(), Some("Finds things."));
+
+        let mut args = ToolArgs::default();
+        let err = args.parser().parse2(quote!(title = "x")).unwrap_err();
+        assert_eq!(err.to_string(), "expected `name` or `description`");
+    }
+
+    #[test]
+    fn result_detection() {
+        assert!(returns_result(&parse_quote!(-> Result<u32, String>)));
+        assert!(returns_result(&parse_quote!(-> std::io::Result<()>)));
+        assert!(!returns_result(&parse_quote!(-> Option<u32>)));
+        assert!(!returns_result(&parse_quote!()));
+    }
+}
@@ -0,0 +1,596 @@
+//! Authentication providers.
+//!
+//! A [`Client`](crate::Client) consults its [`Authenticator`] before every
+//! request. Static API keys and bearer tokens are supported directly, along
+//! with OAuth2 service accounts, gcloud user credentials and the GCE/GKE
+//! metadata server. [`application_default_credentials`] discovers the right
+//! provider from the environment.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use futures_util::future::BoxFuture;
+use serde_derive::Deserialize;
+use tokio::sync::Mutex;
+
+use crate::client::REDACTED;
+use crate::error::*;
+
+/// The OAuth2 scope requested by default.
+pub const DEFAULT_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+
+/// The default OAuth2 token endpoint.
+pub const DEFAULT_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+
+/// Default host of the GCE metadata server.
+const DEFAULT_METADATA_HOST: &str = "metadata.google.internal";
+
+/// Cached tokens are refreshed this long before they expire.
+const REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+/// Lifetime requested for self-signed service account assertions.
+const ASSERTION_LIFETIME: Duration = Duration::from_secs(3600);
+
+/// A credential attached to a single request.
+#[derive(Clone, PartialEq, Eq)]
+pub enum Credential {
+    /// Sent as the `x-goog-api-key` header.
+    ApiKey(String),
+    /// Sent as an `Authorization: Bearer` header.
+    Bearer(String),
+}
+
+impl Credential {
+    /// The secret value of the credential.
+    pub fn secret(&self) -> &str {
+        match self {
+            Credential::ApiKey(s) | Credential::Bearer(s) => s,
+        }
+    }
+}
+
+impl fmt::Debug for Credential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Credential::ApiKey(_) => f.debug_tuple("ApiKey").field(&REDACTED).finish(),
+            Credential::Bearer(_) => f.debug_tuple("Bearer").field(&REDACTED).finish(),
+        }
+    }
+}
+
+/// Supplies credentials for outgoing requests.
+///
+/// Implementations are consulted once per request and are responsible for
+/// caching and refreshing any tokens they hold.
+pub trait Authenticator: Send + Sync + fmt::Debug {
+    /// Returns the credential to attach to the next request.
+    fn credential(&self) -> BoxFuture<'_, Result<Credential>>;
+}
+
+/// A static API key.
+#[derive(Clone)]
+pub struct ApiKey(pub String);
+
+impl fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ApiKey").field(&REDACTED).finish()
+    }
+}
+
+impl Authenticator for ApiKey {
+    fn credential(&self) -> BoxFuture<'_, Result<Credential>> {
+        Box::pin(async move { Ok(Credential::ApiKey(self.0.clone())) })
+    }
+}
+
+/// A static OAuth2 access token, e.g. from `gcloud auth print-access-token`.
+#[derive(Clone)]
+pub struct BearerToken(pub String);
+
+impl fmt::Debug for BearerToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BearerToken").field(&REDACTED).finish()
+    }
+}
+
+impl Authenticator for BearerToken {
+    fn credential(&self) -> BoxFuture<'_, Result<Credential>> {
+        Box::pin(async move { Ok(Credential::Bearer(self.0.clone())) })
+    }
+}
+
+/// An access token together with the instant it stops being usable.
+struct CachedToken {
+    token: String,
+    expires_at: Instant,
+}
+
+/// Caches a bearer token and refreshes it shortly before expiry.
+///
+/// The lock is held while refreshing, so concurrent callers wait for a single
+/// token exchange instead of each starting their own.
+#[derive(Default)]
+struct TokenCache {
+    token: Mutex<Option<CachedToken>>,
+}
+
+impl TokenCache {
+    async fn get<F>(&self, fetch: F) -> Result<Credential>
+    where
+        F: std::future::Future<Output = Result<TokenResponse>>,
+    {
+        let mut guard = self.token.lock().await;
+        if let Some(t) = guard.as_ref() {
+            if Instant::now() + REFRESH_MARGIN < t.expires_at {
+                return Ok(Credential::Bearer(t.token.clone()));
+            }
+        }
+        let resp = fetch.await?;
+        let token = resp.access_token.clone();
+        *guard = Some(CachedToken {
+            token: resp.access_token,
+            expires_at: Instant::now() + Duration::from_secs(resp.expires_in.unwrap_or(3600)),
+        });
+        Ok(Credential::Bearer(token))
+    }
+}
+
+/// The response from an OAuth2 token endpoint.
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+/// Reads a token endpoint response, converting failures into errors. `secrets` sent with
+/// the request are scrubbed from them, as error bodies may echo the request.
+async fn token_response(
+    resp: reqwest::Result<reqwest::Response>,
+    secrets: &[&str],
+) -> Result<TokenResponse> {
+    let result = async {
+        let resp =
+            resp.map_err(|e| GenAiError::auth_source("Token request failed", e.without_url()))?;
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            return Err(GenAiError::auth(format!(
+                "Token request failed with status {}: {}",
+                status.as_u16(),
+                body
+            )));
+        }
+        resp.json()
+            .await
+            .map_err(|e| GenAiError::auth_source("Failed to parse token response", e.without_url()))
+    }
+    .await;
+    result.map_err(|e| secrets.iter().fold(e, |e, s| e.redact(s)))
+}
+
+fn seconds_since_epoch() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// The fields of a service account JSON key file used for authentication.
+#[derive(Clone, Deserialize)]
+pub struct ServiceAccountKey {
+    pub client_email: String,
+    pub private_key: String,
+    pub private_key_id: Option<String>,
+    pub project_id: Option<String>,
+    pub token_uri: Option<String>,
+}
+
+impl fmt::Debug for ServiceAccountKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ServiceAccountKey")
+            .field("client_email", &self.client_email)
+            .field("private_key", &REDACTED)
+            .field("private_key_id", &self.private_key_id)
+            .field("project_id", &self.project_id)
+            .field("token_uri", &self.token_uri)
+            .finish()
+    }
+}
+
+/// Authenticates as a service account by exchanging a signed JWT for an access
+/// token.
+pub struct ServiceAccount {
+    key: ServiceAccountKey,
+    signer: ring::signature::RsaKeyPair,
+    token_uri: String,
+    scopes: Vec<String>,
+    http: reqwest::Client,
+    cache: TokenCache,
+}
+
+impl fmt::Debug for ServiceAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ServiceAccount")
+            .field("key", &self.key)
+            .field("token_uri", &self.token_uri)
+            .field("scopes", &self.scopes)
+            .finish()
+    }
+}
+
+impl ServiceAccount {
+    /// Creates an authenticator from a parsed key.
+    pub fn new(key: ServiceAccountKey) -> Result<Self> {
+        let der = pem_to_der(&key.private_key)?;
+        let signer = ring::signature::RsaKeyPair::from_pkcs8(&der)
+            .map_err(|e| GenAiError::auth(format!("Invalid service account key: {}", e)))?;
+        Ok(Self {
+            token_uri: key
+                .token_uri
+                .clone()
+                .unwrap_or_else(|| DEFAULT_TOKEN_URI.to_string()),
+            key,
+            signer,
+            scopes: vec![DEFAULT_SCOPE.to_string()],
+            http: reqwest::Client::new(),
+            cache: TokenCache::default(),
+        })
+    }
+
+    /// Creates an authenticator from the contents of a JSON key file.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let key = serde_json::from_str(json)
+            .map_err(|e| GenAiError::auth_source("Failed to parse service account key", e))?;
+        Self::new(key)
+    }
+
+    /// Creates an authenticator from a JSON key file on disk.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_json(&read_credentials_file(path.as_ref())?)
+    }
+
+    /// Overrides the token endpoint from the key file.
+    pub fn token_uri(mut self, uri: impl Into<String>) -> Self {
+        self.token_uri = uri.into();
+        self
+    }
+
+    /// Sets the OAuth2 scopes to request.
+    pub fn scopes<I, S>(mut self, scopes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.scopes = scopes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Uses the given HTTP client for token exchanges.
+    pub fn http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Returns the project the key belongs to, if recorded in the key file.
+    pub fn project_id(&self) -> Option<&str> {
+        self.key.project_id.as_deref()
+    }
+
+    /// Builds and signs the JWT assertion sent to the token endpoint.
+    fn assertion(&self) -> Result<String> {
+        let iat = seconds_since_epoch();
+        let mut header = serde_json::json!({"alg": "RS256", "typ": "JWT"});
+        if let Some(kid) = &self.key.private_key_id {
+            header["kid"] = kid.clone().into();
+        }
+        let claims = serde_json::json!({
+            "iss": self.key.client_email,
+            "scope": self.scopes.join(" "),
+            "aud": self.token_uri,
+            "iat": iat,
+            "exp": iat + ASSERTION_LIFETIME.as_secs(),
+        });
+        let message = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header.to_string()),
+            URL_SAFE_NO_PAD.encode(claims.to_string())
+        );
+        let mut signature = vec![0; self.signer.public().modulus_len()];
+        self.signer
+            .sign(
+                &ring::signature::RSA_PKCS1_SHA256,
+                &ring::rand::SystemRandom::new(),
+                message.as_bytes(),
+                &mut signature,
+            )
+            .map_err(|_| GenAiError::auth("Failed to sign JWT assertion"))?;
+        Ok(format!("{}.{}", message, URL_SAFE_NO_PAD.encode(signature)))
+    }
+
+    async fn fetch(&self) -> Result<TokenResponse> {
+        let assertion = self.assertion()?;
+        token_response(
+            self.http
+                .post(&self.token_uri)
+                .form(&[
+                    ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                    ("assertion", assertion.as_str()),
+                ])
+                .send()
+                .await,
+            &[assertion.as_str()],
+        )
+        .await
+    }
+}
+
+impl Authenticator for ServiceAccount {
+    fn credential(&self) -> BoxFuture<'_, Result<Credential>> {
+        Box::pin(self.cache.get(self.fetch()))
+    }
+}
+
+/// Authenticates as a user with a refresh token, as written by
+/// `gcloud auth application-default login`.
+pub struct AuthorizedUser {
+    client_id: String,
+    client_secret: String,
+    refresh_token: String,
+    token_uri: String,
+    http: reqwest::Client,
+    cache: TokenCache,
+}
+
+impl fmt::Debug for AuthorizedUser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthorizedUser")
+            .field("client_id", &self.client_id)
+            .field("client_secret", &REDACTED)
+            .field("refresh_token", &REDACTED)
+            .field("token_uri", &self.token_uri)
+            .finish()
+    }
+}
+
+impl AuthorizedUser {
+    /// Creates an authenticator from OAuth2 client credentials and a refresh token.
+    pub fn new(
+        client_id: impl Into<String>,
+        client_secret: impl Into<String>,
+        refresh_token: impl Into<String>,
+    ) -> Self {
+        Self {
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            refresh_token: refresh_token.into(),
+            token_uri: DEFAULT_TOKEN_URI.to_string(),
+            http: reqwest::Client::new(),
+            cache: TokenCache::default(),
+        }
+    }
+
+    /// Overrides the token endpoint.
+    pub fn token_uri(mut self, uri: impl Into<String>) -> Self {
+        self.token_uri = uri.into();
+        self
+    }
+
+    /// Uses the given HTTP client for token exchanges.
+    pub fn http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    async fn fetch(&self) -> Result<TokenResponse> {
+        token_response(
+            self.http
+                .post(&self.token_uri)
+                .form(&[
+                    ("grant_type", "refresh_token"),
+                    ("client_id", self.client_id.as_str()),
+                    ("client_secret", self.client_secret.as_str()),
+                    ("refresh_token", self.refresh_token.as_str()),
+                ])
+                .send()
+                .await,
+            &[&self.client_secret, &self.refresh_token],
+        )
+        .await
+    }
+}
+
+impl Authenticator for AuthorizedUser {
+    fn credential(&self) -> BoxFuture<'_, Result<Credential>> {
+        Box::pin(self.cache.get(self.fetch()))
+    }
+}
+
+/// Authenticates as the default service account of the current GCE instance,
+/// GKE workload or Cloud Run service via the metadata server.
+pub struct MetadataServer {
+    host: String,
+    http: reqwest::Client,
+    cache: TokenCache,
+}
+
+impl fmt::Debug for MetadataServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MetadataServer")
+            .field("host", &self.host)
+            .finish()
+    }
+}
+
+impl Default for MetadataServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetadataServer {
+    /// Creates an authenticator for the metadata server, honouring the
+    /// `GCE_METADATA_HOST` environment variable.
+    pub fn new() -> Self {
+        Self {
+            host: std::env::var("GCE_METADATA_HOST")
+                .unwrap_or_else(|_| DEFAULT_METADATA_HOST.to_string()),
+            http: reqwest::Client::new(),
+            cache: TokenCache::default(),
+        }
+    }
+
+    /// Overrides the metadata server host, e.g. `127.0.0.1:8080`.
+    pub fn host(mut self, host: impl Into<String>) -> Self {
+        self.host = host.into();
+        self
+    }
+
+    /// Uses the given HTTP client for token requests.
+    pub fn http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    async fn fetch(&self) -> Result<TokenResponse> {
+        let url = format!(
+            "http://{}/computeMetadata/v1/instance/service-accounts/default/token",
+            self.host
+        );
+        token_response(
+            self.http
+                .get(url)
+                .header("Metadata-Flavor", "Google")
+                .send()
+                .await,
+            // The request carries no credentials of ours, so there is nothing to scrub.
+            &[],
+        )
+        .await
+    }
+}
+
+impl Authenticator for MetadataServer {
+    fn credential(&self) -> BoxFuture<'_, Result<Credential>> {
+        Box::pin(self.cache.get(self.fetch()))
+    }
+}
+
+/// Discovers Application Default Credentials.
+///
+/// Sources are checked in order:
+///
+/// 1. The JSON file named by `GOOGLE_APPLICATION_CREDENTIALS`.
+/// 2. The gcloud well-known file, `application_default_credentials.json`, in
+///    `$CLOUDSDK_CONFIG`, `~/.config/gcloud` or `%APPDATA%\gcloud`.
+/// 3. The GCE/GKE metadata server, if the process runs on Google Cloud.
+///
+/// Credential files may hold either a service account key or authorized user
+/// credentials. Fails with [`GenAiError::Auth`] if no source is available.
+pub fn application_default_credentials() -> Result<Arc<dyn Authenticator>> {
+    if let Ok(path) = std::env::var("GOOGLE_APPLICATION_CREDENTIALS") {
+        if !path.is_empty() {
+            return credentials_from_file(Path::new(&path));
+        }
+    }
+    if let Some(path) = well_known_credentials_path().filter(|p| p.is_file()) {
+        return credentials_from_file(&path);
+    }
+    if on_google_cloud() {
+        return Ok(Arc::new(MetadataServer::new()));
+    }
+    Err(GenAiError::auth(
+        "No credentials found: set GOOGLE_APPLICATION_CREDENTIALS, run \
+         `gcloud auth application-default login`, or run on Google Cloud",
+    ))
+}
+
+/// Whether the metadata server should be reachable. Like the official client libraries,
+/// this looks at the environment rather than waiting on a network probe.
+fn on_google_cloud() -> bool {
+    // An explicit metadata host, or Cloud Run and Cloud Functions.
+    if std::env::var_os("GCE_METADATA_HOST").is_some() || std::env::var_os("K_SERVICE").is_some() {
+        return true;
+    }
+    // Compute Engine VMs, including GKE nodes.
+    std::fs::read_to_string("/sys/class/dmi/id/product_name")
+        .is_ok_and(|name| name.trim_start().starts_with("Google"))
+}
+
+/// Loads a service account or authorized user credentials file.
+pub fn credentials_from_file(path: &Path) -> Result<Arc<dyn Authenticator>> {
+    #[derive(Deserialize)]
+    struct CredentialsFile {
+        r#type: Option<String>,
+        client_id: Option<String>,
+        client_secret: Option<String>,
+        refresh_token: Option<String>,
+        token_uri: Option<String>,
+    }
+
+    let json = read_credentials_file(path)?;
+    let file: CredentialsFile = serde_json::from_str(&json)
+        .map_err(|e| GenAiError::auth_source("Failed to parse credentials file", e))?;
+    match file.r#type.as_deref() {
+        Some("service_account") => Ok(Arc::new(ServiceAccount::from_json(&json)?)),
+        Some("authorized_user") => {
+            let (Some(id), Some(secret), Some(refresh)) =
+                (file.client_id, file.client_secret, file.refresh_token)
+            else {
+                return Err(GenAiError::auth(
+                    "Authorized user credentials are incomplete",
+                ));
+            };
+            let mut user = AuthorizedUser::new(id, secret, refresh);
+            if let Some(uri) = file.token_uri {
+                user = user.token_uri(uri);
+            }
+            Ok(Arc::new(user))
+        }
+        other => Err(GenAiError::auth(format!(
+            "Unsupported credentials type: {}",
+            other.unwrap_or("<missing>")
+        ))),
+    }
+}
+
+fn read_credentials_file(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).map_err(|e| {
+        GenAiError::auth_source(
+            format!("Failed to read credentials file {}", path.display()),
+            e,
+        )
+    })
+}
+
+/// The location gcloud writes application default credentials to.
+fn well_known_credentials_path() -> Option<PathBuf> {
+    const FILE: &str = "application_default_credentials.json";
+    if let Ok(dir) = std::env::var("CLOUDSDK_CONFIG") {
+        return Some(PathBuf::from(dir).join(FILE));
+    }
+    if cfg!(windows) {
+        std::env::var("APPDATA")
+            .ok()
+            .map(|d| PathBuf::from(d).join("gcloud").join(FILE))
+    } else {
+        std::env::var("HOME")
+            .ok()
+            .map(|d| PathBuf::from(d).join(".config").join("gcloud").join(FILE))
+    }
+}
+
+/// Decodes the body of a PEM-encoded private key.
+fn pem_to_der(pem: &str) -> Result<Vec<u8>> {
+    let body: String = pem
+        .lines()
+        .filter(|l| !l.starts_with("-----"))
+        .map(str::trim)
+        .collect();
+    STANDARD
+        .decode(body)
+        .map_err(|e| GenAiError::auth_source("Invalid PEM private key", e))
+}
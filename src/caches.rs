@@ -0,0 +1,100 @@
+//! Context caching, for reusing a large prompt prefix across many requests.
+
+use std::pin::Pin;
+use std::time::Duration;
+
+use futures_util::Stream;
+use reqwest::Method;
+
+use crate::client::{paginate, Client};
+use crate::datatypes::{CachedContent, ListCachedContentsResponse};
+use crate::error::*;
+
+pub type CachedContentStream = Pin<Box<dyn Stream<Item = Result<CachedContent>> + Send>>;
+
+impl Client {
+    /// Creates a cache. `cache.model` is required, and one of `ttl` or `expire_time` sets
+    /// its lifetime; the server defaults to one hour otherwise.
+    ///
+    /// Pass the returned handle to [`CachedContent::request`] to use it.
+    pub async fn create_cached_content(&self, mut cache: CachedContent) -> Result<CachedContent> {
+        let model = cache
+            .model
+            .as_deref()
+            .ok_or_else(|| GenAiError::InvalidRequest("CachedContent has no model".to_string()))?;
+        cache.model = Some(self.model_resource(model));
+        let url = format!("{}/cachedContents", self.api_root());
+        let (rb, secret) = self.post(&url).await?;
+        self.send_json(rb.json(&cache))
+            .await
+            .map_err(|e| e.redact(&secret))
+    }
+
+    /// Fetches a cache by name.
+    pub async fn get_cached_content(&self, name: &str) -> Result<CachedContent> {
+        let url = self.cached_content_url(name);
+        let (rb, secret) = self.request(Method::GET, &url).await?;
+        self.send_json(rb).await.map_err(|e| e.redact(&secret))
+    }
+
+    /// Fetches a single page of caches.
+    pub async fn list_cached_contents_page(
+        &self,
+        page_size: Option<u32>,
+        page_token: Option<&str>,
+    ) -> Result<ListCachedContentsResponse> {
+        let url = format!("{}/cachedContents", self.api_root());
+        let (mut rb, secret) = self.request(Method::GET, &url).await?;
+        if let Some(size) = page_size {
+            rb = rb.query(&[("pageSize", size)]);
+        }
+        if let Some(token) = page_token {
+            rb = rb.query(&[("pageToken", token)]);
+        }
+        self.send_json(rb).await.map_err(|e| e.redact(&secret))
+    }
+
+    /// Lists all caches, fetching further pages as the stream is consumed.
+    pub fn list_cached_contents(&self, page_size: Option<u32>) -> CachedContentStream {
+        let client = self.clone();
+        paginate(move |token| {
+            let client = client.clone();
+            async move {
+                let page = client
+                    .list_cached_contents_page(page_size, token.as_deref())
+                    .await?;
+                Ok((page.cached_contents, page.next_page_token))
+            }
+        })
+    }
+
+    /// Extends or shortens the lifetime of a cache to `ttl` from now.
+    pub async fn update_cached_content_ttl(
+        &self,
+        name: &str,
+        ttl: Duration,
+    ) -> Result<CachedContent> {
+        let url = self.cached_content_url(name);
+        let (rb, secret) = self.request(Method::PATCH, &url).await?;
+        let body = CachedContent::default().ttl_duration(ttl);
+        self.send_json(rb.query(&[("updateMask", "ttl")]).json(&body))
+            .await
+            .map_err(|e| e.redact(&secret))
+    }
+
+    /// Deletes a cache.
+    pub async fn delete_cached_content(&self, name: &str) -> Result<()> {
+        let url = self.cached_content_url(name);
+        let (rb, secret) = self.request(Method::DELETE, &url).await?;
+        self.send(rb).await.map_err(|e| e.redact(&secret))?;
+        Ok(())
+    }
+
+    /// Returns the URL of a cache given its ID or resource name.
+    fn cached_content_url(&self, name: &str) -> String {
+        let id = name
+            .rsplit_once("cachedContents/")
+            .map_or(name, |(_, id)| id);
+        format!("{}/cachedContents/{}", self.api_root(), id)
+    }
+}
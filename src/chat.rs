@@ -0,0 +1,202 @@
+//! Multi-turn conversations that keep their own history.
+
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use futures_util::stream::Fuse;
+use futures_util::{Stream, StreamExt};
+
+use crate::client::{blocked_error, Client, ResponseStream};
+use crate::datatypes::{Content, GenerateContentReq, GenerateContentResponse};
+use crate::error::*;
+use crate::stream::StreamAccumulator;
+
+/// The role of turns written by the caller.
+pub const USER_ROLE: &str = "user";
+
+/// The role of turns written by the model.
+pub const MODEL_ROLE: &str = "model";
+
+/// A conversation with a model.
+///
+/// Holds the model, configuration and history, and sends the full history with every
+/// message. A turn is only recorded once the model has replied successfully, so a failed
+/// request leaves the history unchanged and can simply be retried.
+#[derive(Debug, Clone)]
+pub struct ChatSession {
+    client: Client,
+    template: GenerateContentReq,
+    history: Vec<Content>,
+}
+
+impl Client {
+    /// Starts a chat session.
+    ///
+    /// `req` supplies the model and configuration used for every message. Its `contents`
+    /// become the initial history, which must alternate between user and model turns,
+    /// starting with the user and ending with the model.
+    pub fn start_chat(&self, mut req: GenerateContentReq) -> Result<ChatSession> {
+        let history = std::mem::take(&mut req.contents);
+        validate_history(&history)?;
+        Ok(ChatSession {
+            client: self.clone(),
+            template: req,
+            history,
+        })
+    }
+}
+
+impl ChatSession {
+    /// The conversation so far.
+    pub fn history(&self) -> &[Content] {
+        &self.history
+    }
+
+    /// Returns a copy of the history, for later use with [`ChatSession::restore`].
+    pub fn snapshot(&self) -> Vec<Content> {
+        self.history.clone()
+    }
+
+    /// Replaces the history, e.g. with an earlier snapshot.
+    ///
+    /// The history must alternate between user and model turns, starting with the user and
+    /// ending with the model.
+    pub fn restore(&mut self, history: Vec<Content>) -> Result<()> {
+        validate_history(&history)?;
+        self.history = history;
+        Ok(())
+    }
+
+    /// Clears the history.
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+
+    /// Sends a user message and records the model's reply.
+    ///
+    /// A reply without any content leaves the history unchanged.
+    pub async fn send(&mut self, message: Content) -> Result<GenerateContentResponse> {
+        let (message, req) = self.prepare(message)?;
+        let response = self.client.generate_content(req).await?;
+        match reply_content(&response) {
+            Some(reply) => self.record(message, reply),
+            None => {
+                if let Some(err) = blocked_error(&response, true) {
+                    return Err(err);
+                }
+            }
+        }
+        Ok(response)
+    }
+
+    /// Sends a user message and streams the model's reply.
+    ///
+    /// The reply is recorded once the stream has been consumed to the end without errors.
+    /// Dropping the stream early, or a stream without any content, leaves the history
+    /// unchanged.
+    pub async fn send_stream(&mut self, message: Content) -> Result<ChatStream<'_>> {
+        let (message, req) = self.prepare(message)?;
+        let inner = self.client.generate_content_stream(req).await?;
+        Ok(ChatStream {
+            session: self,
+            inner: inner.fuse(),
+            message: Some(message),
+            acc: StreamAccumulator::new(),
+            failed: false,
+        })
+    }
+
+    /// Checks the role of a new message and builds the request that sends it.
+    fn prepare(&self, mut message: Content) -> Result<(Content, GenerateContentReq)> {
+        let role = message.role.get_or_insert_with(|| USER_ROLE.to_string());
+        if role != USER_ROLE {
+            return Err(GenAiError::InvalidRequest(format!(
+                "Chat messages must have role \"{}\", got \"{}\"",
+                USER_ROLE, role
+            )));
+        }
+        let mut req = self.template.clone();
+        req.contents = self.history.clone();
+        req.contents.push(message.clone());
+        Ok((message, req))
+    }
+
+    /// Appends a completed turn.
+    fn record(&mut self, message: Content, mut reply: Content) {
+        reply.role = Some(MODEL_ROLE.to_string());
+        self.history.push(message);
+        self.history.push(reply);
+    }
+}
+
+/// The streamed reply to a [`ChatSession::send_stream`] message.
+///
+/// Yields the raw response chunks and records the aggregated reply when it ends.
+pub struct ChatStream<'a> {
+    session: &'a mut ChatSession,
+    inner: Fuse<ResponseStream>,
+    message: Option<Content>,
+    acc: StreamAccumulator,
+    failed: bool,
+}
+
+impl Stream for ChatStream<'_> {
+    type Item = Result<GenerateContentResponse>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        match ready!(this.inner.poll_next_unpin(cx)) {
+            Some(Ok(response)) => {
+                this.acc.push(&response);
+                Poll::Ready(Some(Ok(response)))
+            }
+            Some(Err(e)) => {
+                this.failed = true;
+                Poll::Ready(Some(Err(e)))
+            }
+            None => {
+                let reply = reply_content(this.acc.response());
+                if let (Some(message), Some(reply), false) =
+                    (this.message.take(), reply, this.failed)
+                {
+                    this.session.record(message, reply);
+                }
+                Poll::Ready(None)
+            }
+        }
+    }
+}
+
+/// The content of the first candidate, if it has any parts.
+fn reply_content(response: &GenerateContentResponse) -> Option<Content> {
+    response
+        .candidate()?
+        .content
+        .clone()
+        .filter(|c| c.parts.as_ref().is_some_and(|p| !p.is_empty()))
+}
+
+/// Checks that a history alternates between user and model turns, starting with the user
+/// and ending with the model.
+fn validate_history(history: &[Content]) -> Result<()> {
+    for (i, content) in history.iter().enumerate() {
+        let expected = if i.is_multiple_of(2) {
+            USER_ROLE
+        } else {
+            MODEL_ROLE
+        };
+        let role = content.role.as_deref().unwrap_or_default();
+        if role != expected {
+            return Err(GenAiError::InvalidRequest(format!(
+                "Chat history entry {} has role \"{}\", expected \"{}\"",
+                i, role, expected
+            )));
+        }
+    }
+    if !history.len().is_multiple_of(2) {
+        return Err(GenAiError::InvalidRequest(
+            "Chat history must end with a model turn".into(),
+        ));
+    }
+    Ok(())
+}
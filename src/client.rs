@@ -0,0 +1,844 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use derive_setters::*;
+use eventsource_stream::{Event, EventStreamError, Eventsource};
+use futures_util::{Stream, StreamExt, TryStreamExt};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use reqwest::Method;
+
+use crate::auth::{ApiKey, Authenticator, BearerToken, Credential};
+use crate::datatypes;
+use crate::error::*;
+use crate::options::{earliest, guard, RequestOptions};
+use crate::retry::{retry_after, RetryPolicy};
+
+/// The default endpoint for the Gemini Developer API.
+pub const DEFAULT_BASE_URL: &str = "https://generativelanguage.googleapis.com";
+
+/// The default API version for the Gemini Developer API.
+pub const DEFAULT_API_VERSION: &str = "v1beta";
+
+/// The default API version for Vertex AI.
+pub const DEFAULT_VERTEX_API_VERSION: &str = "v1";
+
+/// Header used to pass the API key. Keeping the key out of the URL keeps it out of
+/// proxy logs and transport error messages.
+const API_KEY_HEADER: &str = "x-goog-api-key";
+
+/// Placeholder substituted for secrets in `Debug` and error output.
+pub(crate) const REDACTED: &str = "[REDACTED]";
+
+/// The largest number of requests the server accepts in one `batchEmbedContents` call.
+pub const MAX_BATCH_EMBED_REQUESTS: usize = 100;
+
+pub type ResponseStream =
+    Pin<Box<dyn Stream<Item = Result<datatypes::GenerateContentResponse>> + Send>>;
+
+pub type ModelStream = Pin<Box<dyn Stream<Item = Result<datatypes::Model>> + Send>>;
+
+/// The platform a [`Client`] talks to.
+///
+/// Both backends accept the same request and response types; only URLs and
+/// authentication differ.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Backend {
+    /// The Gemini Developer API at `generativelanguage.googleapis.com`.
+    #[default]
+    GeminiApi,
+    /// Vertex AI, addressed by Google Cloud project and location.
+    VertexAi { project: String, location: String },
+}
+
+impl Backend {
+    /// Creates a Vertex AI backend for the given project and location.
+    pub fn vertex_ai(project: impl Into<String>, location: impl Into<String>) -> Self {
+        Backend::VertexAi {
+            project: project.into(),
+            location: location.into(),
+        }
+    }
+
+    /// The base URL used when none is configured explicitly.
+    fn default_base_url(&self) -> String {
+        match self {
+            Backend::GeminiApi => DEFAULT_BASE_URL.to_string(),
+            Backend::VertexAi { location, .. } if location == "global" => {
+                "https://aiplatform.googleapis.com".to_string()
+            }
+            Backend::VertexAi { location, .. } => {
+                format!("https://{}-aiplatform.googleapis.com", location)
+            }
+        }
+    }
+
+    /// The API version used when none is configured explicitly.
+    fn default_api_version(&self) -> &'static str {
+        match self {
+            Backend::GeminiApi => DEFAULT_API_VERSION,
+            Backend::VertexAi { .. } => DEFAULT_VERTEX_API_VERSION,
+        }
+    }
+}
+
+/// Builder for a [`Client`].
+///
+/// All options are optional. When no `http_client` is supplied, a new pooled
+/// `reqwest::Client` is constructed on `build`.
+#[derive(Clone, Default, Setters)]
+#[setters(strip_option, into)]
+pub struct ClientBuilder {
+    /// The platform to send requests to.
+    backend: Option<Backend>,
+    /// API key used to authenticate requests.
+    api_key: Option<String>,
+    /// OAuth2 access token sent as an `Authorization: Bearer` header. Usually
+    /// required for Vertex AI.
+    bearer_token: Option<String>,
+    /// Supplies credentials per request. Takes precedence over `api_key` and
+    /// `bearer_token`.
+    #[setters(skip)]
+    authenticator: Option<Arc<dyn Authenticator>>,
+    /// Base URL of the API, without the version component.
+    base_url: Option<String>,
+    /// API version, e.g. `v1beta`.
+    api_version: Option<String>,
+    /// Overall timeout applied to each request, except streamed responses, which are
+    /// bounded with [`RequestOptions`] instead.
+    timeout: Option<Duration>,
+    /// Connect timeout. Only used when no `http_client` is supplied.
+    connect_timeout: Option<Duration>,
+    /// Headers sent with every request.
+    default_headers: Option<HeaderMap>,
+    /// A pre-configured `reqwest::Client` to use for all requests.
+    http_client: Option<reqwest::Client>,
+    /// Policy for retrying transient failures. No retries are made by default.
+    retry_policy: Option<RetryPolicy>,
+    /// Return [`GenAiError::Blocked`] from `generate_content` when the prompt or response
+    /// is blocked or no candidates come back, rather than an empty response.
+    strict: Option<bool>,
+}
+
+impl fmt::Debug for ClientBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientBuilder")
+            .field("backend", &self.backend)
+            .field("api_key", &self.api_key.as_ref().map(|_| REDACTED))
+            .field(
+                "bearer_token",
+                &self.bearer_token.as_ref().map(|_| REDACTED),
+            )
+            .field("authenticator", &self.authenticator)
+            .field("base_url", &self.base_url)
+            .field("api_version", &self.api_version)
+            .field("timeout", &self.timeout)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("default_headers", &self.default_headers)
+            .field("http_client", &self.http_client)
+            .field("retry_policy", &self.retry_policy)
+            .field("strict", &self.strict)
+            .finish()
+    }
+}
+
+impl ClientBuilder {
+    /// Sets the authenticator consulted before every request.
+    pub fn authenticator(mut self, auth: impl Authenticator + 'static) -> Self {
+        self.authenticator = Some(Arc::new(auth));
+        self
+    }
+
+    /// Sets a shared authenticator consulted before every request.
+    pub fn shared_authenticator(mut self, auth: Arc<dyn Authenticator>) -> Self {
+        self.authenticator = Some(auth);
+        self
+    }
+
+    /// Builds the client.
+    pub fn build(self) -> Result<Client> {
+        let http = match self.http_client {
+            Some(c) => c,
+            None => {
+                let mut builder = reqwest::Client::builder();
+                if let Some(t) = self.connect_timeout {
+                    builder = builder.connect_timeout(t);
+                }
+                builder.build().map_err(GenAiError::transport)?
+            }
+        };
+        let backend = self.backend.unwrap_or_default();
+        let auth = match (self.authenticator, self.bearer_token, self.api_key) {
+            (Some(a), _, _) => Some(a),
+            (None, Some(t), _) => Some(Arc::new(BearerToken(t)) as Arc<dyn Authenticator>),
+            (None, None, Some(k)) => Some(Arc::new(ApiKey(k)) as Arc<dyn Authenticator>),
+            (None, None, None) => None,
+        };
+        Ok(Client {
+            inner: Arc::new(ClientInner {
+                auth,
+                base_url: self
+                    .base_url
+                    .unwrap_or_else(|| backend.default_base_url())
+                    .trim_end_matches('/')
+                    .to_string(),
+                api_version: self
+                    .api_version
+                    .unwrap_or_else(|| backend.default_api_version().to_string()),
+                backend,
+                timeout: self.timeout,
+                default_headers: self.default_headers.unwrap_or_default(),
+                retry: self.retry_policy.unwrap_or_else(RetryPolicy::none),
+                strict: self.strict.unwrap_or_default(),
+                http,
+            }),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct ClientInner {
+    backend: Backend,
+    auth: Option<Arc<dyn Authenticator>>,
+    base_url: String,
+    api_version: String,
+    timeout: Option<Duration>,
+    default_headers: HeaderMap,
+    retry: RetryPolicy,
+    strict: bool,
+    http: reqwest::Client,
+}
+
+/// A client for the Google Generative Language API.
+///
+/// The client is cheap to clone and can be shared across tasks; all clones
+/// share the same configuration and connection pool.
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: Arc<ClientInner>,
+}
+
+impl Client {
+    /// Creates a client with default settings and the given API key.
+    pub fn new(api_key: impl Into<String>) -> Result<Self> {
+        Self::builder().api_key(api_key).build()
+    }
+
+    /// Returns a builder for configuring a client.
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    /// Returns the backend this client sends requests to.
+    pub fn backend(&self) -> &Backend {
+        &self.inner.backend
+    }
+
+    /// Returns the versioned root URL under which resources are addressed.
+    pub(crate) fn api_root(&self) -> String {
+        match &self.inner.backend {
+            Backend::GeminiApi => format!("{}/{}", self.inner.base_url, self.inner.api_version),
+            Backend::VertexAi { project, location } => format!(
+                "{}/{}/projects/{}/locations/{}",
+                self.inner.base_url, self.inner.api_version, project, location
+            ),
+        }
+    }
+
+    /// Returns the versioned root URL for media uploads.
+    pub(crate) fn upload_root(&self) -> String {
+        format!("{}/upload/{}", self.inner.base_url, self.inner.api_version)
+    }
+
+    /// Returns the URL for invoking `method` on `model`.
+    fn model_url(&self, model: &str, method: &str) -> String {
+        let model = model.strip_prefix("models/").unwrap_or(model);
+        match self.inner.backend {
+            Backend::GeminiApi => format!("{}/models/{}:{}", self.api_root(), model, method),
+            Backend::VertexAi { .. } => {
+                let model = model
+                    .strip_prefix("publishers/google/models/")
+                    .unwrap_or(model);
+                format!(
+                    "{}/publishers/google/models/{}:{}",
+                    self.api_root(),
+                    model,
+                    method
+                )
+            }
+        }
+    }
+
+    /// Returns the resource name of `model` in the form the backend expects in request
+    /// bodies.
+    pub(crate) fn model_resource(&self, model: &str) -> String {
+        let model = model.rsplit_once("models/").map_or(model, |(_, m)| m);
+        match &self.inner.backend {
+            Backend::GeminiApi => qualified_model(model),
+            Backend::VertexAi { project, location } => format!(
+                "projects/{}/locations/{}/publishers/google/models/{}",
+                project, location, model
+            ),
+        }
+    }
+
+    /// Fails unless the client talks to the Gemini Developer API.
+    pub(crate) fn require_gemini_api(&self, what: &str) -> Result<()> {
+        match self.inner.backend {
+            Backend::GeminiApi => Ok(()),
+            Backend::VertexAi { .. } => Err(GenAiError::InvalidRequest(format!(
+                "{} is only supported by the Gemini Developer API",
+                what
+            ))),
+        }
+    }
+
+    /// Starts a POST request. See [`Client::request`].
+    pub(crate) async fn post(&self, url: &str) -> Result<(reqwest::RequestBuilder, String)> {
+        self.request(Method::POST, url).await
+    }
+
+    /// Starts a request with the client's default headers, credentials and timeout
+    /// applied. Also returns the secret attached to the request, so that callers can scrub
+    /// it from errors.
+    pub(crate) async fn request(
+        &self,
+        method: Method,
+        url: &str,
+    ) -> Result<(reqwest::RequestBuilder, String)> {
+        self.request_with_timeout(method, url, self.inner.timeout)
+            .await
+    }
+
+    /// Like [`Client::request`], with an explicit overall timeout in place of the client's.
+    async fn request_with_timeout(
+        &self,
+        method: Method,
+        url: &str,
+        timeout: Option<Duration>,
+    ) -> Result<(reqwest::RequestBuilder, String)> {
+        let mut rb = self
+            .inner
+            .http
+            .request(method, url)
+            .headers(self.inner.default_headers.clone());
+        if let Some(t) = timeout {
+            rb = rb.timeout(t);
+        }
+        let Some(auth) = &self.inner.auth else {
+            return Ok((rb, String::new()));
+        };
+        let credential = auth.credential().await?;
+        let (name, value) = match &credential {
+            Credential::ApiKey(k) => (API_KEY_HEADER, k.clone()),
+            Credential::Bearer(t) => (AUTHORIZATION.as_str(), format!("Bearer {}", t)),
+        };
+        let mut value = HeaderValue::from_str(&value)
+            .map_err(|_| GenAiError::auth("Credential is not a valid header value"))?;
+        value.set_sensitive(true);
+        Ok((rb.header(name, value), credential.secret().to_string()))
+    }
+
+    /// Sends a request, retrying transient failures according to the client's retry
+    /// policy. Returns the first successful response.
+    pub(crate) async fn send(&self, rb: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let policy = &self.inner.retry;
+        let start = Instant::now();
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let req = rb
+                .try_clone()
+                .ok_or_else(|| GenAiError::Internal("Failed to clone request".to_string()))?;
+            let (err, hint) = match req.send().await {
+                Ok(resp) if resp.status().is_success() => return Ok(resp),
+                Ok(resp) => {
+                    let status = resp.status().as_u16();
+                    let header_hint = retry_after(resp.headers());
+                    let err = GenAiError::from_response(resp).await;
+                    if !policy.is_retryable_status(status) {
+                        return Err(err);
+                    }
+                    let hint = header_hint.or_else(|| err.retry_delay());
+                    (err, hint)
+                }
+                Err(e) => {
+                    let err = GenAiError::transport(e);
+                    if !err.is_retryable() {
+                        return Err(err);
+                    }
+                    (err, None)
+                }
+            };
+            match policy.next_delay(attempt, start.elapsed(), hint) {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => return Err(err),
+            }
+        }
+    }
+
+    /// Sends a request and deserializes a successful JSON response.
+    pub(crate) async fn send_json<T: serde::de::DeserializeOwned>(
+        &self,
+        rb: reqwest::RequestBuilder,
+    ) -> Result<T> {
+        decode_json(self.send(rb).await?).await
+    }
+
+    /// Generates streaming content from the API.
+    ///
+    /// Returns a stream of `GenerateContentResponse` objects that can be consumed asynchronously.
+    /// Uses Server-Sent Events (SSE) to stream the responses. The initial connection is
+    /// retried according to the client's retry policy; the stream itself is never
+    /// reconnected, as that would repeat the generation.
+    ///
+    /// The stream ends after the server finishes the response. If the connection closes
+    /// before a chunk with a finish reason arrived, the last item is
+    /// [`GenAiError::StreamTruncated`]. The stream also ends after yielding any other error.
+    pub async fn generate_content_stream(
+        &self,
+        req: datatypes::GenerateContentReq,
+    ) -> Result<ResponseStream> {
+        self.generate_content_stream_with_options(req, RequestOptions::default())
+            .await
+    }
+
+    /// Like [`Client::generate_content_stream`], bounded by the timeouts and cancellation
+    /// token in `options`.
+    ///
+    /// If the stream runs out of time or is cancelled, it yields [`GenAiError::Timeout`] or
+    /// [`GenAiError::Cancelled`] and ends, closing the connection.
+    pub async fn generate_content_stream_with_options(
+        &self,
+        req: datatypes::GenerateContentReq,
+        options: RequestOptions,
+    ) -> Result<ResponseStream> {
+        let deadline = options.effective_deadline();
+        let url = format!(
+            "{}?alt=sse",
+            self.model_url(&req.model, "streamGenerateContent")
+        );
+        let connect = async {
+            // The client's timeout would count the whole body against it and cut long
+            // answers off, so streams are bounded by `options` alone.
+            let (rb, secret) = self.request_with_timeout(Method::POST, &url, None).await?;
+            let response = self
+                .send(rb.json(&req))
+                .await
+                .map_err(|e| e.redact(&secret))?;
+            Ok::<_, GenAiError>((response, secret))
+        };
+        let (response, secret) = guard(options.cancellation.as_ref(), deadline, connect).await??;
+        let state = SseState {
+            events: Box::pin(response.bytes_stream().eventsource()),
+            secret,
+            deadline,
+            options,
+            strict: self.inner.strict,
+            finished: false,
+            done: false,
+        };
+        Ok(Box::pin(futures_util::stream::unfold(state, next_sse_item)))
+    }
+
+    /// Generates content from the API in a single request.
+    ///
+    /// Makes a single POST request to the API and returns the complete response. If the
+    /// client is strict, a blocked or empty response is returned as
+    /// [`GenAiError::Blocked`].
+    pub async fn generate_content(
+        &self,
+        req: datatypes::GenerateContentReq,
+    ) -> Result<datatypes::GenerateContentResponse> {
+        self.generate_content_with_options(req, RequestOptions::default())
+            .await
+    }
+
+    /// Like [`Client::generate_content`], bounded by the timeout, deadline and cancellation
+    /// token in `options`.
+    pub async fn generate_content_with_options(
+        &self,
+        req: datatypes::GenerateContentReq,
+        options: RequestOptions,
+    ) -> Result<datatypes::GenerateContentResponse> {
+        let deadline = options.effective_deadline();
+        let call = async {
+            let url = self.model_url(&req.model, "generateContent");
+            let (rb, secret) = self.post(&url).await?;
+            self.send_json(rb.json(&req))
+                .await
+                .map_err(|e| e.redact(&secret))
+        };
+        let response = guard(options.cancellation.as_ref(), deadline, call).await??;
+        match blocked_error(&response, true) {
+            Some(err) if self.inner.strict => Err(err),
+            _ => Ok(response),
+        }
+    }
+
+    /// Counts the tokens in a prompt without generating a response.
+    ///
+    /// Vertex AI cannot count against cached content, so a request with `cached_content`
+    /// set fails there with [`GenAiError::InvalidRequest`].
+    pub async fn count_tokens(
+        &self,
+        req: impl Into<datatypes::CountTokensReq>,
+    ) -> Result<datatypes::CountTokensResponse> {
+        let req = req.into();
+        let url = self.model_url(&req.model, "countTokens");
+        let body = match self.inner.backend {
+            // The Gemini API only accepts a system instruction, tools and cached content
+            // when they are wrapped in a full generate request.
+            Backend::GeminiApi => {
+                serde_json::json!({
+                    "generateContentRequest": datatypes::GenerateContentReq {
+                        model: qualified_model(&req.model),
+                        contents: req.contents,
+                        system_instruction: req.system_instruction,
+                        tools: req.tools,
+                        cached_content: req.cached_content,
+                        generation_config: req.generation_config,
+                        ..Default::default()
+                    }
+                })
+            }
+            Backend::VertexAi { .. } => {
+                if req.cached_content.is_some() {
+                    return Err(GenAiError::InvalidRequest(
+                        "countTokens with cached content is only supported by the Gemini \
+                         Developer API"
+                            .to_string(),
+                    ));
+                }
+                serde_json::to_value(&req).map_err(|e| {
+                    GenAiError::InvalidRequest(format!("Failed to serialize request: {}", e))
+                })?
+            }
+        };
+        let (rb, secret) = self.post(&url).await?;
+        self.send_json(rb.json(&body))
+            .await
+            .map_err(|e| e.redact(&secret))
+    }
+
+    /// Computes an embedding for a single piece of content.
+    ///
+    /// Only supported by the Gemini Developer API.
+    pub async fn embed_content(
+        &self,
+        mut req: datatypes::EmbedContentReq,
+    ) -> Result<datatypes::ContentEmbedding> {
+        self.require_gemini_api("embedContent")?;
+        let url = self.model_url(&req.model, "embedContent");
+        req.model = qualified_model(&req.model);
+        let (rb, secret) = self.post(&url).await?;
+        self.send_json::<datatypes::EmbedContentResponse>(rb.json(&req))
+            .await
+            .map(|r| r.embedding)
+            .map_err(|e| e.redact(&secret))
+    }
+
+    /// Computes embeddings for many pieces of content with `model`.
+    ///
+    /// The `model` of each request is overwritten. Requests are sent in chunks of
+    /// [`MAX_BATCH_EMBED_REQUESTS`], and the embeddings are returned in the order of
+    /// `reqs`. Only supported by the Gemini Developer API.
+    pub async fn batch_embed_contents(
+        &self,
+        model: &str,
+        reqs: Vec<datatypes::EmbedContentReq>,
+    ) -> Result<Vec<datatypes::ContentEmbedding>> {
+        self.require_gemini_api("batchEmbedContents")?;
+        let url = self.model_url(model, "batchEmbedContents");
+        let qualified = qualified_model(model);
+        let mut embeddings = Vec::with_capacity(reqs.len());
+        let mut reqs = reqs.into_iter().peekable();
+        while reqs.peek().is_some() {
+            let chunk: Vec<_> = reqs
+                .by_ref()
+                .take(MAX_BATCH_EMBED_REQUESTS)
+                .map(|r| r.model(qualified.clone()))
+                .collect();
+            let expected = chunk.len();
+            let (rb, secret) = self.post(&url).await?;
+            let resp: datatypes::BatchEmbedContentsResponse = self
+                .send_json(rb.json(&serde_json::json!({ "requests": chunk })))
+                .await
+                .map_err(|e| e.redact(&secret))?;
+            if resp.embeddings.len() != expected {
+                let message = format!(
+                    "Expected {} embeddings, got {}",
+                    expected,
+                    resp.embeddings.len()
+                );
+                let body = serde_json::to_string(&resp).unwrap_or_default();
+                return Err(GenAiError::unexpected(body, message));
+            }
+            embeddings.extend(resp.embeddings);
+        }
+        Ok(embeddings)
+    }
+
+    /// Fetches metadata about a model.
+    ///
+    /// Only supported by the Gemini Developer API.
+    pub async fn get_model(&self, model: &str) -> Result<datatypes::Model> {
+        self.require_gemini_api("models.get")?;
+        let url = format!("{}/{}", self.api_root(), qualified_model(model));
+        let (rb, secret) = self.request(Method::GET, &url).await?;
+        self.send_json(rb).await.map_err(|e| e.redact(&secret))
+    }
+
+    /// Fetches a single page of available models.
+    ///
+    /// Only supported by the Gemini Developer API.
+    pub async fn list_models_page(
+        &self,
+        page_size: Option<u32>,
+        page_token: Option<&str>,
+    ) -> Result<datatypes::ListModelsResponse> {
+        self.require_gemini_api("models.list")?;
+        let url = format!("{}/models", self.api_root());
+        let (mut rb, secret) = self.request(Method::GET, &url).await?;
+        if let Some(size) = page_size {
+            rb = rb.query(&[("pageSize", size)]);
+        }
+        if let Some(token) = page_token {
+            rb = rb.query(&[("pageToken", token)]);
+        }
+        self.send_json(rb).await.map_err(|e| e.redact(&secret))
+    }
+
+    /// Lists all available models, fetching further pages as the stream is consumed.
+    ///
+    /// Only supported by the Gemini Developer API.
+    pub fn list_models(&self, page_size: Option<u32>) -> ModelStream {
+        let client = self.clone();
+        paginate(move |token| {
+            let client = client.clone();
+            async move {
+                let page = client.list_models_page(page_size, token.as_deref()).await?;
+                Ok((page.models, page.next_page_token))
+            }
+        })
+    }
+}
+
+/// Turns a paged list endpoint into a stream of items. `fetch` is called with the token of
+/// the page to fetch, starting with `None`, and returns the page's items and next token.
+pub(crate) fn paginate<T, F, Fut>(fetch: F) -> Pin<Box<dyn Stream<Item = Result<T>> + Send>>
+where
+    T: Send + 'static,
+    F: Fn(Option<String>) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<(Vec<T>, Option<String>)>> + Send + 'static,
+{
+    let pages =
+        futures_util::stream::try_unfold((Some(None), fetch), |(token, fetch)| async move {
+            let Some(token) = token else {
+                return Ok(None);
+            };
+            let (items, next) = fetch(token).await?;
+            let next = next.filter(|t| !t.is_empty()).map(Some);
+            Ok(Some((items, (next, fetch))))
+        });
+    Box::pin(
+        pages
+            .map_ok(|items| futures_util::stream::iter(items.into_iter().map(Ok)))
+            .try_flatten(),
+    )
+}
+
+/// Reads a response body and deserializes it as JSON, keeping the body if it doesn't parse.
+pub(crate) async fn decode_json<T: serde::de::DeserializeOwned>(
+    response: reqwest::Response,
+) -> Result<T> {
+    let body = response.text().await.map_err(GenAiError::transport)?;
+    serde_json::from_str(&body).map_err(|source| GenAiError::Decode { body, source })
+}
+
+/// Returns a model name in the `models/{model}` form used in request bodies.
+pub(crate) fn qualified_model(model: &str) -> String {
+    format!("models/{}", model.strip_prefix("models/").unwrap_or(model))
+}
+
+type EventStream = Pin<
+    Box<dyn Stream<Item = std::result::Result<Event, EventStreamError<reqwest::Error>>> + Send>,
+>;
+
+/// The state of a response stream.
+struct SseState {
+    events: EventStream,
+    /// The secret to scrub from errors.
+    secret: String,
+    /// When the whole stream must be complete.
+    deadline: Option<Instant>,
+    options: RequestOptions,
+    /// Whether a blocked chunk is turned into an error.
+    strict: bool,
+    /// Whether a chunk marking the response as complete has been received.
+    finished: bool,
+    /// Whether the stream has ended.
+    done: bool,
+}
+
+/// Produces the next item of a response stream from its server-sent events.
+async fn next_sse_item(
+    mut state: SseState,
+) -> Option<(Result<datatypes::GenerateContentResponse>, SseState)> {
+    if state.done {
+        return None;
+    }
+    loop {
+        let idle = state.options.idle_timeout.map(|t| Instant::now() + t);
+        let limit = earliest(state.deadline, idle);
+        let next = guard(
+            state.options.cancellation.as_ref(),
+            limit,
+            state.events.next(),
+        );
+        let item =
+            match next.await {
+                Err(e) => Err(e),
+                Ok(None) if state.finished => return None,
+                Ok(None) => Err(GenAiError::StreamTruncated),
+                Ok(Some(Ok(event))) if event.data == "[DONE]" => return None,
+                Ok(Some(Ok(event))) if event.data.trim().is_empty() => continue,
+                Ok(Some(Ok(event))) => parse_event(&event.data).and_then(|response| {
+                    match blocked_error(&response, false) {
+                        Some(err) if state.strict => Err(err),
+                        _ => Ok(response),
+                    }
+                }),
+                Ok(Some(Err(EventStreamError::Transport(e)))) => Err(GenAiError::transport(e)),
+                Ok(Some(Err(e))) => Err(GenAiError::Stream {
+                    message: match e {
+                        EventStreamError::Utf8(_) => "Response stream is not valid UTF-8",
+                        _ => "Response stream is not a valid event stream",
+                    }
+                    .to_string(),
+                    source: Some(Box::new(e)),
+                }),
+            };
+        match &item {
+            Ok(response) => state.finished |= is_final(response),
+            Err(_) => {
+                // Drop the response now rather than when the stream is, so the connection
+                // closes right away.
+                state.events = Box::pin(futures_util::stream::empty());
+                state.done = true;
+            }
+        }
+        let item = item.map_err(|e| e.redact(&state.secret));
+        return Some((item, state));
+    }
+}
+
+/// Parses the data of a single server-sent event.
+fn parse_event(data: &str) -> Result<datatypes::GenerateContentResponse> {
+    // Errors that occur after the stream has started arrive as a data event.
+    if let Some(err) = ApiError::parse(data) {
+        let status = err.code.and_then(|c| u16::try_from(c).ok()).unwrap_or(500);
+        return Err(GenAiError::remote(status, HashMap::new(), data.to_string()));
+    }
+    serde_json::from_str(data).map_err(|source| GenAiError::Decode {
+        body: data.to_string(),
+        source,
+    })
+}
+
+/// The [`GenAiError::Blocked`] describing a blocked response, or one without candidates if
+/// `require_candidate` is set.
+pub(crate) fn blocked_error(
+    response: &datatypes::GenerateContentResponse,
+    require_candidate: bool,
+) -> Option<GenAiError> {
+    if let Some(feedback) = &response.prompt_feedback {
+        if feedback.block_reason.is_some() {
+            return Some(GenAiError::Blocked {
+                block_reason: feedback.block_reason.clone(),
+                finish_reason: None,
+                message: feedback.block_reason_message.clone(),
+                safety_ratings: feedback.safety_ratings.clone().unwrap_or_default(),
+            });
+        }
+    }
+    let Some(candidate) = response.candidate() else {
+        return require_candidate.then(|| GenAiError::Blocked {
+            block_reason: None,
+            finish_reason: None,
+            message: None,
+            safety_ratings: Vec::new(),
+        });
+    };
+    let reason = candidate.finish_reason.as_ref()?;
+    reason.is_blocked().then(|| GenAiError::Blocked {
+        block_reason: None,
+        finish_reason: Some(reason.clone()),
+        message: candidate.finish_message.clone(),
+        safety_ratings: candidate.safety_ratings.clone().unwrap_or_default(),
+    })
+}
+
+/// Whether a chunk completes the response: it carries a finish reason, or the prompt was
+/// blocked.
+fn is_final(response: &datatypes::GenerateContentResponse) -> bool {
+    let finish = response
+        .candidates
+        .iter()
+        .flatten()
+        .any(|c| c.finish_reason.is_some());
+    let blocked = response
+        .prompt_feedback
+        .as_ref()
+        .is_some_and(|f| f.block_reason.is_some());
+    finish || blocked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vertex_default_urls() {
+        let regional = Backend::vertex_ai("p", "us-central1");
+        assert_eq!(
+            regional.default_base_url(),
+            "https://us-central1-aiplatform.googleapis.com"
+        );
+        assert_eq!(regional.default_api_version(), "v1");
+
+        let global = Backend::vertex_ai("p", "global");
+        assert_eq!(
+            global.default_base_url(),
+            "https://aiplatform.googleapis.com"
+        );
+
+        let client = Client::builder().backend(global).build().unwrap();
+        assert_eq!(
+            client.api_root(),
+            "https://aiplatform.googleapis.com/v1/projects/p/locations/global"
+        );
+        assert_eq!(
+            client.model_url("gemini-2.0-flash", "generateContent"),
+            "https://aiplatform.googleapis.com/v1/projects/p/locations/global/publishers/google/\
+             models/gemini-2.0-flash:generateContent"
+        );
+    }
+
+    #[test]
+    fn gemini_default_urls() {
+        let client = Client::new("key").unwrap();
+        assert_eq!(
+            client.model_url("models/gemini-2.0-flash", "generateContent"),
+            "https://generativelanguage.googleapis.com/v1beta/models/\
+             gemini-2.0-flash:generateContent"
+        );
+        assert_eq!(
+            client.model_resource("gemini-2.0-flash"),
+            "models/gemini-2.0-flash"
+        );
+    }
+}
@@ -1,185 +1,258 @@
+use std::collections::HashMap;
+use std::path::Path;
+
 use derive_setters::*;
 use serde_derive::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 use time::Date;
 
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum Outcome {
-    #[default]
-    Unspecified,
-    Ok,
-    Failed,
-    DeadlineExceeded,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum Language {
-    Unspecified,
-    Python,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum Type {
-    Unspecified,
-    String,
-    Number,
-    Integer,
-    Boolean,
-    Array,
-    Object,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum HarmCategory {
-    #[default]
-    Unspecified,
-    HateSpeech,
-    DangerousContent,
-    Harassment,
-    SexuallyExplicit,
-    CivicIntegrity,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub enum HarmBlockMethod {
-    #[serde(rename = "HARM_BLOCK_METHOD_UNSPECIFIED")]
-    Unspecified,
-    #[serde(rename = "SEVERITY")]
-    Severity,
-    #[serde(rename = "PROBABILITY")]
-    Probability,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum HarmBlockThreshold {
-    #[default]
-    Unspecified,
-    BlockLowAndAbove,
-    BlockMediumAndAbove,
-    BlockOnlyHigh,
-    BlockNone,
-    Off,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum Mode {
-    Unspecified,
-    Dynamic,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum FinishReason {
-    Unspecified,
-    Stop,
-    MaxTokens,
-    Safety,
-    Recitation,
-    Other,
-    Blocklist,
-    ProhibitedContent,
-    Spii,
-    MalformedFunctionCall,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub enum HarmProbability {
-    #[serde(rename = "HARM_PROBABILITY_UNSPECIFIED")]
-    Unspecified,
-    #[serde(rename = "NEGLIGIBLE")]
-    Negligible,
-    #[serde(rename = "LOW")]
-    Low,
-    #[serde(rename = "MEDIUM")]
-    Medium,
-    #[serde(rename = "HIGH")]
-    High,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum HarmSeverity {
-    Unspecified,
-    HarmSeverityNegligible,
-    HarmSeverityLow,
-    HarmSeverityMedium,
-    HarmSeverityHigh,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum BlockedReason {
-    Unspecified,
-    Safety,
-    Other,
-    Blocklist,
-    ProhibitedContent,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum DynamicRetrievalConfigMode {
-    Unspecified,
-    ModeDynamic,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub enum FunctionCallingConfigMode {
-    #[serde(rename = "MODE_UNSPECIFIED")]
-    Unspecified,
-    #[serde(rename = "AUTO")]
-    Auto,
-    #[serde(rename = "ANY")]
-    Any,
-    #[serde(rename = "NONE")]
-    None,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum MediaResolution {
-    Unspecified,
-    Low,
-    Medium,
-    High,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum MaskReferenceMode {
-    MaskModeDefault,
-    MaskModeUserProvided,
-    MaskModeBackground,
-    MaskModeForeground,
-    MaskModeSemantic,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum ControlReferenceType {
-    ControlTypeDefault,
-    ControlTypeCanny,
-    ControlTypeScribble,
-    ControlTypeFaceMesh,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum SubjectReferenceType {
-    SubjectTypeDefault,
-    SubjectTypePerson,
-    SubjectTypeAnimal,
-    SubjectTypeProduct,
+/// Defines an API enum that serializes to fixed wire names and tolerates values added to the
+/// API after this crate was released. Unrecognised values deserialize to `Unknown`, which
+/// preserves the raw string so it round-trips unchanged.
+macro_rules! api_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($(#[$vmeta:meta])* $variant:ident = $wire:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$vmeta])* $variant,)*
+            /// A value not known to this version of the crate.
+            Unknown(String),
+        }
+
+        impl $name {
+            /// The wire name of the value.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $wire,)*
+                    $name::Unknown(s) => s,
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(s: &str) -> Self {
+                match s {
+                    $($wire => $name::$variant,)*
+                    other => $name::Unknown(other.to_string()),
+                }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                s.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                let s = <std::borrow::Cow<'de, str> as serde::Deserialize>::deserialize(d)?;
+                Ok($name::from(s.as_ref()))
+            }
+        }
+    };
+}
+
+api_enum! {
+    #[derive(Default)]
+    pub enum Outcome {
+        #[default]
+        Unspecified = "OUTCOME_UNSPECIFIED",
+        Ok = "OUTCOME_OK",
+        Failed = "OUTCOME_FAILED",
+        DeadlineExceeded = "OUTCOME_DEADLINE_EXCEEDED",
+    }
+}
+
+api_enum! {
+    pub enum Language {
+        Unspecified = "LANGUAGE_UNSPECIFIED",
+        Python = "PYTHON",
+    }
+}
+
+api_enum! {
+    pub enum Type {
+        Unspecified = "TYPE_UNSPECIFIED",
+        String = "STRING",
+        Number = "NUMBER",
+        Integer = "INTEGER",
+        Boolean = "BOOLEAN",
+        Array = "ARRAY",
+        Object = "OBJECT",
+    }
+}
+
+api_enum! {
+    #[derive(Default)]
+    pub enum HarmCategory {
+        #[default]
+        Unspecified = "HARM_CATEGORY_UNSPECIFIED",
+        HateSpeech = "HARM_CATEGORY_HATE_SPEECH",
+        DangerousContent = "HARM_CATEGORY_DANGEROUS_CONTENT",
+        Harassment = "HARM_CATEGORY_HARASSMENT",
+        SexuallyExplicit = "HARM_CATEGORY_SEXUALLY_EXPLICIT",
+        CivicIntegrity = "HARM_CATEGORY_CIVIC_INTEGRITY",
+    }
+}
+
+api_enum! {
+    pub enum HarmBlockMethod {
+        Unspecified = "HARM_BLOCK_METHOD_UNSPECIFIED",
+        Severity = "SEVERITY",
+        Probability = "PROBABILITY",
+    }
+}
+
+api_enum! {
+    #[derive(Default)]
+    pub enum HarmBlockThreshold {
+        #[default]
+        Unspecified = "HARM_BLOCK_THRESHOLD_UNSPECIFIED",
+        BlockLowAndAbove = "BLOCK_LOW_AND_ABOVE",
+        BlockMediumAndAbove = "BLOCK_MEDIUM_AND_ABOVE",
+        BlockOnlyHigh = "BLOCK_ONLY_HIGH",
+        BlockNone = "BLOCK_NONE",
+        Off = "OFF",
+    }
+}
+
+api_enum! {
+    pub enum Mode {
+        Unspecified = "MODE_UNSPECIFIED",
+        Dynamic = "MODE_DYNAMIC",
+    }
+}
+
+api_enum! {
+    pub enum FinishReason {
+        Unspecified = "FINISH_REASON_UNSPECIFIED",
+        Stop = "STOP",
+        MaxTokens = "MAX_TOKENS",
+        Safety = "SAFETY",
+        Recitation = "RECITATION",
+        Language = "LANGUAGE",
+        Other = "OTHER",
+        Blocklist = "BLOCKLIST",
+        ProhibitedContent = "PROHIBITED_CONTENT",
+        Spii = "SPII",
+        MalformedFunctionCall = "MALFORMED_FUNCTION_CALL",
+        ImageSafety = "IMAGE_SAFETY",
+    }
+}
+
+impl FinishReason {
+    /// Whether generation stopped because the output was blocked rather than finished.
+    pub fn is_blocked(&self) -> bool {
+        matches!(
+            self,
+            FinishReason::Safety
+                | FinishReason::Recitation
+                | FinishReason::Blocklist
+                | FinishReason::ProhibitedContent
+                | FinishReason::Spii
+                | FinishReason::ImageSafety
+        )
+    }
+}
+
+api_enum! {
+    pub enum HarmProbability {
+        Unspecified = "HARM_PROBABILITY_UNSPECIFIED",
+        Negligible = "NEGLIGIBLE",
+        Low = "LOW",
+        Medium = "MEDIUM",
+        High = "HIGH",
+    }
+}
+
+api_enum! {
+    pub enum HarmSeverity {
+        Unspecified = "HARM_SEVERITY_UNSPECIFIED",
+        HarmSeverityNegligible = "HARM_SEVERITY_NEGLIGIBLE",
+        HarmSeverityLow = "HARM_SEVERITY_LOW",
+        HarmSeverityMedium = "HARM_SEVERITY_MEDIUM",
+        HarmSeverityHigh = "HARM_SEVERITY_HIGH",
+    }
+}
+
+api_enum! {
+    pub enum BlockedReason {
+        Unspecified = "BLOCKED_REASON_UNSPECIFIED",
+        Safety = "SAFETY",
+        Other = "OTHER",
+        Blocklist = "BLOCKLIST",
+        ProhibitedContent = "PROHIBITED_CONTENT",
+    }
+}
+
+api_enum! {
+    pub enum DynamicRetrievalConfigMode {
+        Unspecified = "MODE_UNSPECIFIED",
+        ModeDynamic = "MODE_DYNAMIC",
+    }
+}
+
+api_enum! {
+    pub enum FunctionCallingConfigMode {
+        Unspecified = "MODE_UNSPECIFIED",
+        Auto = "AUTO",
+        Any = "ANY",
+        None = "NONE",
+    }
+}
+
+api_enum! {
+    pub enum MediaResolution {
+        Unspecified = "MEDIA_RESOLUTION_UNSPECIFIED",
+        Low = "MEDIA_RESOLUTION_LOW",
+        Medium = "MEDIA_RESOLUTION_MEDIUM",
+        High = "MEDIA_RESOLUTION_HIGH",
+    }
+}
+
+api_enum! {
+    pub enum MaskReferenceMode {
+        MaskModeDefault = "MASK_MODE_DEFAULT",
+        MaskModeUserProvided = "MASK_MODE_USER_PROVIDED",
+        MaskModeBackground = "MASK_MODE_BACKGROUND",
+        MaskModeForeground = "MASK_MODE_FOREGROUND",
+        MaskModeSemantic = "MASK_MODE_SEMANTIC",
+    }
+}
+
+api_enum! {
+    pub enum ControlReferenceType {
+        ControlTypeDefault = "CONTROL_TYPE_DEFAULT",
+        ControlTypeCanny = "CONTROL_TYPE_CANNY",
+        ControlTypeScribble = "CONTROL_TYPE_SCRIBBLE",
+        ControlTypeFaceMesh = "CONTROL_TYPE_FACE_MESH",
+    }
+}
+
+api_enum! {
+    pub enum SubjectReferenceType {
+        SubjectTypeDefault = "SUBJECT_TYPE_DEFAULT",
+        SubjectTypePerson = "SUBJECT_TYPE_PERSON",
+        SubjectTypeAnimal = "SUBJECT_TYPE_ANIMAL",
+        SubjectTypeProduct = "SUBJECT_TYPE_PRODUCT",
+    }
 }
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct VideoMetadata {
     pub end_offset: Option<String>,
@@ -188,6 +261,7 @@ pub struct VideoMetadata {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct CodeExecutionResult {
     pub outcome: Outcome,
@@ -195,12 +269,14 @@ pub struct CodeExecutionResult {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct ExecutableCode {
     pub code: String,
     pub language: Language,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct FileData {
     pub file_uri: String,
     pub mime_type: String,
@@ -208,6 +284,7 @@ pub struct FileData {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct FunctionCall {
     pub id: Option<String>,
@@ -217,6 +294,7 @@ pub struct FunctionCall {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct FunctionResponse {
     pub id: Option<String>,
@@ -225,13 +303,48 @@ pub struct FunctionResponse {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct Blob {
+    /// Raw bytes, base64 encoded on the wire.
+    #[serde(with = "base64_bytes")]
     pub data: Vec<u8>,
     pub mime_type: String,
 }
 
+impl Blob {
+    /// Creates a blob from raw bytes.
+    pub fn new(mime_type: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            data: data.into(),
+            mime_type: mime_type.into(),
+        }
+    }
+
+    /// Creates a blob from raw bytes, detecting the MIME type from the data.
+    pub fn from_bytes(data: impl Into<Vec<u8>>) -> Self {
+        let data = data.into();
+        let mime_type = crate::media::guess_mime_type(None, &data);
+        Self::new(mime_type, data)
+    }
+
+    /// Reads a file into a blob, detecting the MIME type from its contents or extension.
+    pub fn from_path(path: impl AsRef<Path>) -> crate::error::Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).map_err(|e| {
+            crate::error::GenAiError::InvalidRequest(format!(
+                "Failed to read {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let mime_type = crate::media::guess_mime_type(Some(path), &data);
+        Ok(Self::new(mime_type, data))
+    }
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct Part {
     pub video_metadata: Option<VideoMetadata>,
@@ -242,18 +355,56 @@ pub struct Part {
     pub function_response: Option<FunctionResponse>,
     pub inline_data: Option<Blob>,
     pub text: Option<String>,
+    /// Whether `text` is the model's reasoning rather than part of its answer.
+    pub thought: Option<bool>,
+    /// Opaque signature of the model's reasoning, to be sent back unchanged in later turns.
+    pub thought_signature: Option<String>,
+}
+
+impl Part {
+    /// Whether this part holds the model's reasoning rather than its answer.
+    pub fn is_thought(&self) -> bool {
+        self.thought == Some(true)
+    }
+
+    /// Creates a part holding inline bytes of the given MIME type.
+    pub fn inline_bytes(mime_type: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        Self::default().inline_data(Blob::new(mime_type, data))
+    }
+
+    /// Creates a part holding the contents of a file, detecting its MIME type.
+    pub fn inline_file(path: impl AsRef<Path>) -> crate::error::Result<Self> {
+        Ok(Self::default().inline_data(Blob::from_path(path)?))
+    }
 }
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct Content {
     pub parts: Option<Vec<Part>>,
     pub role: Option<String>,
 }
 
+impl Content {
+    /// Creates content with the given role and parts.
+    pub fn new(role: impl Into<String>, parts: Vec<Part>) -> Self {
+        Self {
+            parts: Some(parts),
+            role: Some(role.into()),
+        }
+    }
+
+    /// Creates a user turn holding a single text part.
+    pub fn user_text(text: impl Into<String>) -> Self {
+        Self::new("user", vec![Part::default().text(text)])
+    }
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 /// Schema that defines the format of input and output data.
 /// Represents a select subset of an OpenAPI 3.0 schema object.
@@ -301,13 +452,33 @@ pub struct Schema {
     /// Optional. Schema of the elements of Type.ARRAY.
     pub items: Option<Box<Schema>>,
     /// Optional. Properties of Type.OBJECT.
-    pub properties: Option<std::collections::HashMap<String, Schema>>,
+    pub properties: Option<HashMap<String, Schema>>,
     /// Optional. Required properties of Type.OBJECT.
     pub required: Option<Vec<String>>,
 }
 
+impl Schema {
+    /// Creates a schema of the given type.
+    pub fn new(schema_type: Type) -> Self {
+        <Self as Default>::default().schema_type(schema_type)
+    }
+
+    /// Sets the type of the data.
+    pub fn schema_type(mut self, schema_type: Type) -> Self {
+        self.r#type = Some(schema_type);
+        self
+    }
+
+    /// Sets the possible values of a string.
+    pub fn enum_values<S: Into<String>>(mut self, values: impl IntoIterator<Item = S>) -> Self {
+        self.r#enum = Some(values.into_iter().map(Into::into).collect());
+        self
+    }
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 /// Safety settings.
 pub struct SafetySetting {
@@ -321,6 +492,7 @@ pub struct SafetySetting {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 /// Defines a function that the model can generate JSON inputs for.
 pub struct FunctionDeclaration {
@@ -339,6 +511,7 @@ pub struct GoogleSearch {}
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 /// Describes the options to customize dynamic retrieval.
 pub struct DynamicRetrievalConfig {
@@ -350,6 +523,7 @@ pub struct DynamicRetrievalConfig {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
 /// Tool to retrieve public web data for grounding, powered by Google.
 pub struct GoogleSearchRetrieval {
     /// Specifies the dynamic retrieval configuration for the given source.
@@ -357,6 +531,7 @@ pub struct GoogleSearchRetrieval {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
 /// Retrieve from Vertex AI Search datastore for grounding.
 pub struct VertexAISearch {
     /// Required. Fully-qualified Vertex AI Search data store resource ID.
@@ -365,6 +540,7 @@ pub struct VertexAISearch {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 /// The definition of the RAG resource.
 pub struct VertexRAGStoreRAGResource {
@@ -376,6 +552,7 @@ pub struct VertexRAGStoreRAGResource {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct VertexRAGStore {
     pub rag_corpora: Option<Vec<String>>,
@@ -386,6 +563,7 @@ pub struct VertexRAGStore {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct Retrieval {
     pub vertex_ai_search: Option<VertexAISearch>,
@@ -397,6 +575,7 @@ pub struct ToolCodeExecution {}
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 /// Tool details of a tool that the model may use to generate a response.
 pub struct Tool {
@@ -414,6 +593,7 @@ pub struct Tool {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct FunctionCallingConfig {
     pub mode: Option<FunctionCallingConfigMode>,
@@ -422,6 +602,7 @@ pub struct FunctionCallingConfig {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct ToolConfig {
     pub function_calling_config: Option<FunctionCallingConfig>,
 }
@@ -429,6 +610,7 @@ pub struct ToolConfig {
 #[skip_serializing_none]
 /// The configuration for the prebuilt speaker to use.
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct PrebuiltVoiceConfig {
     /// The name of the prebuilt voice to use.
     pub voice_name: Option<String>,
@@ -437,6 +619,7 @@ pub struct PrebuiltVoiceConfig {
 #[skip_serializing_none]
 /// The configuration for the voice to use.
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct VoiceConfig {
     /// The configuration for the speaker to use.
     pub prebuilt_voice_config: Option<PrebuiltVoiceConfig>,
@@ -445,6 +628,7 @@ pub struct VoiceConfig {
 #[skip_serializing_none]
 /// The speech generation configuration.
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct SpeechConfig {
     /// The configuration for the speaker to use.
     pub voice_config: Option<VoiceConfig>,
@@ -453,6 +637,7 @@ pub struct SpeechConfig {
 #[skip_serializing_none]
 /// When automated routing is specified, the routing will be determined by the pretrained routing model.
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct GenerationConfigRoutingConfigAutoRoutingMode {
     /// The model routing preference.
     pub model_routing_preference: Option<String>,
@@ -461,6 +646,7 @@ pub struct GenerationConfigRoutingConfigAutoRoutingMode {
 #[skip_serializing_none]
 /// When manual routing is set, the specified model will be used directly.
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct GenerationConfigRoutingConfigManualRoutingMode {
     /// The model name to use. Only the public LLM models are accepted.
     pub model_name: Option<String>,
@@ -469,6 +655,7 @@ pub struct GenerationConfigRoutingConfigManualRoutingMode {
 #[skip_serializing_none]
 /// The configuration for routing the request to a specific model.
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct GenerationConfigRoutingConfig {
     /// Automated routing.
@@ -479,6 +666,7 @@ pub struct GenerationConfigRoutingConfig {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default, Setters)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct GenerateContentParameters {
     pub model: Option<String>,
@@ -488,10 +676,12 @@ pub struct GenerateContentParameters {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct Citation {
     pub end_index: Option<i64>,
     pub license: Option<String>,
+    #[serde(default, with = "google_date")]
     pub publication_date: Option<Date>,
     pub start_index: Option<i64>,
     pub title: Option<String>,
@@ -500,12 +690,14 @@ pub struct Citation {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct CitationMetadata {
     pub citations: Option<Vec<Citation>>,
 }
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct GroundingChunkRetrievedContext {
     pub text: Option<String>,
@@ -515,6 +707,7 @@ pub struct GroundingChunkRetrievedContext {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct GroundingChunkWeb {
     pub title: Option<String>,
@@ -523,6 +716,7 @@ pub struct GroundingChunkWeb {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct GroundingChunk {
     pub retrieved_context: Option<GroundingChunkRetrievedContext>,
@@ -531,6 +725,7 @@ pub struct GroundingChunk {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct Segment {
     pub end_index: Option<i64>,
@@ -541,6 +736,7 @@ pub struct Segment {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct GroundingSupport {
     pub confidence_scores: Option<Vec<f64>>,
@@ -550,20 +746,25 @@ pub struct GroundingSupport {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct RetrievalMetadata {
     pub google_search_dynamic_retrieval_score: Option<f64>,
 }
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct SearchEntryPoint {
     pub rendered_content: Option<String>,
+    /// Base64 encoded on the wire.
+    #[serde(default, with = "base64_bytes_opt")]
     pub sdk_blob: Option<Vec<u8>>,
 }
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct GroundingMetadata {
     pub grounding_chunks: Option<Vec<GroundingChunk>>,
@@ -576,6 +777,7 @@ pub struct GroundingMetadata {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct LogprobsResultCandidate {
     pub log_probability: Option<f64>,
@@ -585,12 +787,14 @@ pub struct LogprobsResultCandidate {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct LogprobsResultTopCandidates {
     pub candidates: Option<Vec<LogprobsResultCandidate>>,
 }
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct LogprobsResult {
     pub chosen_candidates: Option<Vec<LogprobsResultCandidate>>,
@@ -599,6 +803,7 @@ pub struct LogprobsResult {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct SafetyRating {
     pub blocked: Option<bool>,
@@ -611,6 +816,7 @@ pub struct SafetyRating {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct Candidate {
     pub content: Option<Content>,
@@ -627,6 +833,7 @@ pub struct Candidate {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct GenerateContentResponsePromptFeedback {
     pub block_reason: Option<BlockedReason>,
@@ -636,26 +843,93 @@ pub struct GenerateContentResponsePromptFeedback {
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct GenerateContentResponseUsageMetadata {
     pub cached_content_token_count: Option<i64>,
     pub candidates_token_count: Option<i64>,
     pub prompt_token_count: Option<i64>,
+    /// Tokens spent on the model's reasoning. Not included in `candidates_token_count`.
+    pub thoughts_token_count: Option<i64>,
+    /// Tokens in the results of tool calls made by the model.
+    pub tool_use_prompt_token_count: Option<i64>,
     pub total_token_count: Option<i64>,
+    pub prompt_tokens_details: Option<Vec<ModalityTokenCount>>,
+    pub cache_tokens_details: Option<Vec<ModalityTokenCount>>,
+    pub candidates_tokens_details: Option<Vec<ModalityTokenCount>>,
+    pub tool_use_prompt_tokens_details: Option<Vec<ModalityTokenCount>>,
+    /// How the request was billed, e.g. `ON_DEMAND`. Vertex AI only.
+    pub traffic_type: Option<String>,
 }
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct GenerateContentResponse {
     pub candidates: Option<Vec<Candidate>>,
+    /// Identifies the response. Shared by all chunks of a stream.
+    pub response_id: Option<String>,
+    /// When the response was created. Vertex AI only.
+    pub create_time: Option<String>,
     pub model_version: Option<String>,
     pub prompt_feedback: Option<GenerateContentResponsePromptFeedback>,
     pub usage_metadata: Option<GenerateContentResponseUsageMetadata>,
 }
 
+impl GenerateContentResponse {
+    /// The first candidate, which is the only one unless more were requested.
+    pub fn candidate(&self) -> Option<&Candidate> {
+        self.candidates.as_ref()?.first()
+    }
+
+    /// The concatenated text parts of the first candidate, or `None` if it has none.
+    /// Thought parts are left out.
+    pub fn text(&self) -> Option<String> {
+        let mut texts = self
+            .candidate()?
+            .content
+            .as_ref()?
+            .parts
+            .iter()
+            .flatten()
+            .filter(|p| !p.is_thought())
+            .filter_map(|p| p.text.as_deref())
+            .peekable();
+        texts.peek()?;
+        Some(texts.collect())
+    }
+
+    /// The function calls in the first candidate.
+    pub fn function_calls(&self) -> Vec<&FunctionCall> {
+        self.candidate()
+            .and_then(|c| c.content.as_ref()?.parts.as_ref())
+            .into_iter()
+            .flatten()
+            .filter_map(|p| p.function_call.as_ref())
+            .collect()
+    }
+
+    /// Why the prompt was blocked, if it was.
+    pub fn blocked_reason(&self) -> Option<&BlockedReason> {
+        self.prompt_feedback.as_ref()?.block_reason.as_ref()
+    }
+
+    /// Why the first candidate stopped.
+    pub fn finish_reason(&self) -> Option<&FinishReason> {
+        self.candidate()?.finish_reason.as_ref()
+    }
+
+    /// Whether the prompt or the first candidate was blocked.
+    pub fn is_blocked(&self) -> bool {
+        self.blocked_reason().is_some()
+            || self.finish_reason().is_some_and(FinishReason::is_blocked)
+    }
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct GenerateContentConfig {
     pub system_instruction: Option<Content>,
@@ -677,14 +951,16 @@ pub struct GenerateContentConfig {
     pub tools: Option<Vec<Tool>>,
     pub tool_config: Option<ToolConfig>,
     pub cached_content: Option<String>,
-    pub response_modalities: Option<Vec<String>>,
+    pub response_modalities: Option<Vec<Modality>>,
     pub media_resolution: Option<MediaResolution>,
     pub speech_config: Option<SpeechConfig>,
+    pub thinking_config: Option<ThinkingConfig>,
 }
 
 /// Configuration for generation settings.
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
 pub struct GenerationConfig {
     /// Optional. If enabled, audio timestamp will be included.
@@ -697,35 +973,571 @@ pub struct GenerationConfig {
     pub logprobs: Option<i64>,
     /// Optional. The maximum number of output tokens to generate per message.
     pub max_output_tokens: Option<i64>,
+    /// Optional. If specified, the media resolution specified will be used.
+    pub media_resolution: Option<MediaResolution>,
     /// Optional. Positive penalties.
     pub presence_penalty: Option<f64>,
     /// Optional. If true, export the logprobs results in response.
     pub response_logprobs: Option<bool>,
     /// Optional. Output response MIME type of the generated candidate text.
     pub response_mime_type: Option<String>,
+    /// Optional. The requested modalities of the response.
+    pub response_modalities: Option<Vec<Modality>>,
     /// Optional. Schema object allows the definition of input and output data types.
     pub response_schema: Option<Schema>,
     /// Optional. Routing configuration.
     pub routing_config: Option<GenerationConfigRoutingConfig>,
     /// Optional. Seed.
     pub seed: Option<i64>,
+    /// Optional. The speech generation configuration.
+    pub speech_config: Option<SpeechConfig>,
     /// Optional. Stop sequences.
     pub stop_sequences: Option<Vec<String>>,
     /// Optional. Controls the randomness of predictions.
     pub temperature: Option<f64>,
+    /// Optional. The thinking features configuration.
+    pub thinking_config: Option<ThinkingConfig>,
     /// Optional. If specified, top-k sampling will be used.
     pub top_k: Option<f64>,
     /// Optional. If specified, nucleus sampling will be used.
     pub top_p: Option<f64>,
 }
 
+/// Configuration for the thinking features of models that support them.
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
 #[setters(strip_option, into)]
+pub struct ThinkingConfig {
+    /// Optional. Whether to include thoughts in the response, if the model returns them.
+    pub include_thoughts: Option<bool>,
+    /// Optional. The number of thought tokens the model may generate. 0 disables
+    /// thinking, and -1 lets the model decide.
+    pub thinking_budget: Option<i64>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
+#[setters(strip_option, into)]
+/// The body of a `generateContent` or `streamGenerateContent` request.
 pub struct GenerateContentReq {
+    /// Required. The model to use, e.g. `gemini-2.0-flash` or `models/gemini-2.0-flash`.
+    pub model: String,
+    /// Required. The content of the current conversation with the model.
+    pub contents: Vec<Content>,
+    /// Optional. Configuration options for model generation and outputs.
+    pub generation_config: Option<GenerationConfig>,
+    /// Optional. Instructions for the model to steer it toward better performance.
+    pub system_instruction: Option<Content>,
+    /// Optional. Tools the model may use to generate the next response.
+    pub tools: Option<Vec<Tool>>,
+    /// Optional. Configuration for any tools specified in the request.
+    pub tool_config: Option<ToolConfig>,
+    /// Optional. Settings for blocking unsafe content.
+    pub safety_settings: Option<Vec<SafetySetting>>,
+    /// Optional. The name of cached content to use as context, e.g. `cachedContents/abc`.
+    pub cached_content: Option<String>,
+    /// Optional. User-defined metadata labels. Vertex AI only.
+    pub labels: Option<HashMap<String, String>>,
+}
+
+impl GenerateContentReq {
+    /// Applies every field of a `GenerateContentConfig`, splitting the top-level request
+    /// fields from those belonging in `generation_config`. Fields that are unset in
+    /// `config` leave the request unchanged.
+    pub fn config(mut self, config: GenerateContentConfig) -> Self {
+        let GenerateContentConfig {
+            system_instruction,
+            temperature,
+            top_p,
+            top_k,
+            candidate_count,
+            max_output_tokens,
+            stop_sequences,
+            response_logprobs,
+            logprobs,
+            presence_penalty,
+            frequency_penalty,
+            seed,
+            response_mime_type,
+            response_schema,
+            routing_config,
+            safety_settings,
+            tools,
+            tool_config,
+            cached_content,
+            response_modalities,
+            media_resolution,
+            speech_config,
+            thinking_config,
+        } = config;
+        self.system_instruction = system_instruction.or(self.system_instruction);
+        self.safety_settings = safety_settings.or(self.safety_settings);
+        self.tools = tools.or(self.tools);
+        self.tool_config = tool_config.or(self.tool_config);
+        self.cached_content = cached_content.or(self.cached_content);
+
+        let gc = self.generation_config.take().unwrap_or_default();
+        let gc = GenerationConfig {
+            audio_timestamp: gc.audio_timestamp,
+            candidate_count: candidate_count.or(gc.candidate_count),
+            frequency_penalty: frequency_penalty.or(gc.frequency_penalty),
+            logprobs: logprobs.or(gc.logprobs),
+            max_output_tokens: max_output_tokens.or(gc.max_output_tokens),
+            media_resolution: media_resolution.or(gc.media_resolution),
+            presence_penalty: presence_penalty.or(gc.presence_penalty),
+            response_logprobs: response_logprobs.or(gc.response_logprobs),
+            response_mime_type: response_mime_type.or(gc.response_mime_type),
+            response_modalities: response_modalities.or(gc.response_modalities),
+            response_schema: response_schema.or(gc.response_schema),
+            routing_config: routing_config.or(gc.routing_config),
+            seed: seed.or(gc.seed),
+            speech_config: speech_config.or(gc.speech_config),
+            stop_sequences: stop_sequences.or(gc.stop_sequences),
+            temperature: temperature.or(gc.temperature),
+            thinking_config: thinking_config.or(gc.thinking_config),
+            top_k: top_k.or(gc.top_k),
+            top_p: top_p.or(gc.top_p),
+        };
+        // Avoid sending an empty `generationConfig` object.
+        if serde_json::to_value(&gc).is_ok_and(|v| v.as_object().is_some_and(|o| !o.is_empty())) {
+            self.generation_config = Some(gc);
+        }
+        self
+    }
+}
+
+impl TryFrom<GenerateContentParameters> for GenerateContentReq {
+    type Error = crate::error::GenAiError;
+
+    fn try_from(params: GenerateContentParameters) -> Result<Self, Self::Error> {
+        let model = params.model.ok_or_else(|| {
+            crate::error::GenAiError::InvalidRequest(
+                "GenerateContentParameters has no model".into(),
+            )
+        })?;
+        let req = GenerateContentReq::default()
+            .model(model)
+            .contents(params.contents.unwrap_or_default());
+        Ok(match params.config {
+            Some(config) => req.config(config),
+            None => req,
+        })
+    }
+}
+
+/// The body of a `countTokens` request.
+///
+/// Accepts the same inputs as a [`GenerateContentReq`], so a request can be
+/// measured before it is sent.
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
+#[setters(strip_option, into)]
+pub struct CountTokensReq {
+    /// Required. The model to count tokens for. Sent in the URL, not the body.
+    #[serde(skip)]
     pub model: String,
+    /// The content to count tokens for.
     pub contents: Vec<Content>,
-    #[serde(rename = "generationConfig")]
+    /// Optional. Instructions for the model to steer it toward better performance.
+    pub system_instruction: Option<Content>,
+    /// Optional. Tools the model may use to generate the next response.
+    pub tools: Option<Vec<Tool>>,
+    /// Optional. The name of cached content to count against. Gemini API only.
+    pub cached_content: Option<String>,
+    /// Optional. Configuration options for model generation and outputs.
     pub generation_config: Option<GenerationConfig>,
+}
+
+impl From<GenerateContentReq> for CountTokensReq {
+    fn from(req: GenerateContentReq) -> Self {
+        Self {
+            model: req.model,
+            contents: req.contents,
+            system_instruction: req.system_instruction,
+            tools: req.tools,
+            cached_content: req.cached_content,
+            generation_config: req.generation_config,
+        }
+    }
+}
+
+impl From<&GenerateContentReq> for CountTokensReq {
+    fn from(req: &GenerateContentReq) -> Self {
+        req.clone().into()
+    }
+}
+
+api_enum! {
+    pub enum Modality {
+        Unspecified = "MODALITY_UNSPECIFIED",
+        Text = "TEXT",
+        Image = "IMAGE",
+        Video = "VIDEO",
+        Audio = "AUDIO",
+        Document = "DOCUMENT",
+    }
+}
+
+/// The number of tokens attributed to a single input modality.
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
+#[setters(strip_option, into)]
+pub struct ModalityTokenCount {
+    pub modality: Option<Modality>,
+    pub token_count: Option<i64>,
+}
+
+/// The response to a `countTokens` request.
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
+#[setters(strip_option, into)]
+pub struct CountTokensResponse {
+    /// The total number of tokens in the prompt, including cached content.
+    pub total_tokens: Option<i64>,
+    /// The number of tokens in the cached part of the prompt.
+    pub cached_content_token_count: Option<i64>,
+    /// The total number of billable characters. Vertex AI only.
+    pub total_billable_characters: Option<i64>,
+    /// Per-modality breakdown of the prompt tokens.
+    pub prompt_tokens_details: Option<Vec<ModalityTokenCount>>,
+    /// Per-modality breakdown of the cached tokens.
+    pub cache_tokens_details: Option<Vec<ModalityTokenCount>>,
+}
+
+api_enum! {
+    #[derive(Default)]
+    pub enum TaskType {
+        #[default]
+        Unspecified = "TASK_TYPE_UNSPECIFIED",
+        RetrievalQuery = "RETRIEVAL_QUERY",
+        RetrievalDocument = "RETRIEVAL_DOCUMENT",
+        SemanticSimilarity = "SEMANTIC_SIMILARITY",
+        Classification = "CLASSIFICATION",
+        Clustering = "CLUSTERING",
+        QuestionAnswering = "QUESTION_ANSWERING",
+        FactVerification = "FACT_VERIFICATION",
+        CodeRetrievalQuery = "CODE_RETRIEVAL_QUERY",
+    }
+}
+
+/// The body of an `embedContent` request, and one entry of a `batchEmbedContents` request.
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
+#[setters(strip_option, into)]
+pub struct EmbedContentReq {
+    /// Required. The embedding model, e.g. `text-embedding-004`.
+    pub model: String,
+    /// Required. The content to embed. Only text parts are counted.
+    pub content: Content,
+    /// Optional. The task the embedding will be used for.
+    pub task_type: Option<TaskType>,
+    /// Optional. A title for the text. Only applicable with `TaskType::RetrievalDocument`.
+    pub title: Option<String>,
+    /// Optional. Reduced dimension for the output embedding. Excess values are truncated.
+    pub output_dimensionality: Option<i64>,
+}
+
+/// An embedding vector.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentEmbedding {
+    #[serde(default)]
+    pub values: Vec<f32>,
+}
+
+/// The response to an `embedContent` request.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbedContentResponse {
+    pub embedding: ContentEmbedding,
+}
+
+/// The response to a `batchEmbedContents` request.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchEmbedContentsResponse {
+    #[serde(default)]
+    pub embeddings: Vec<ContentEmbedding>,
+}
+
+/// Information about a generative model.
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
+#[setters(strip_option, into)]
+pub struct Model {
+    /// The resource name of the model, e.g. `models/gemini-2.0-flash`.
+    pub name: Option<String>,
+    /// The name of the base model.
+    pub base_model_id: Option<String>,
+    /// The version number of the model.
+    pub version: Option<String>,
+    /// The human-readable name of the model.
+    pub display_name: Option<String>,
+    /// A short description of the model.
+    pub description: Option<String>,
+    /// Maximum number of input tokens allowed for this model.
+    pub input_token_limit: Option<i64>,
+    /// Maximum number of output tokens available for this model.
+    pub output_token_limit: Option<i64>,
+    /// The API methods the model supports, e.g. `generateContent`.
+    pub supported_generation_methods: Option<Vec<String>>,
+    /// The default sampling temperature.
+    pub temperature: Option<f64>,
+    /// The maximum temperature this model can use.
+    pub max_temperature: Option<f64>,
+    /// The default nucleus sampling value.
+    pub top_p: Option<f64>,
+    /// The default top-k sampling value.
+    pub top_k: Option<i64>,
+    /// Whether the model can reason before answering.
+    pub thinking: Option<bool>,
+}
+
+impl Model {
+    /// Whether the model supports the given API method, e.g. `generateContent`.
+    pub fn supports(&self, method: &str) -> bool {
+        self.supported_generation_methods
+            .as_ref()
+            .is_some_and(|m| m.iter().any(|x| x == method))
+    }
+}
+
+/// A page of results from `models.list`.
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ListModelsResponse {
+    #[serde(default)]
+    pub models: Vec<Model>,
+    /// A token for the next page, absent on the last page.
+    pub next_page_token: Option<String>,
+}
+
+api_enum! {
+    /// The processing state of an uploaded file.
+    #[derive(Default)]
+    pub enum FileState {
+        #[default]
+        Unspecified = "STATE_UNSPECIFIED",
+        Processing = "PROCESSING",
+        Active = "ACTIVE",
+        Failed = "FAILED",
+    }
+}
+
+/// A file uploaded through the Files API.
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
+#[setters(strip_option, into)]
+pub struct File {
+    /// The resource name of the file, e.g. `files/abc-123`.
+    pub name: Option<String>,
+    /// The human-readable name of the file.
+    pub display_name: Option<String>,
+    pub mime_type: Option<String>,
+    /// The size of the file in bytes, as a decimal string.
+    pub size_bytes: Option<String>,
+    pub create_time: Option<String>,
+    pub update_time: Option<String>,
+    /// When the file will be deleted by the server.
+    pub expiration_time: Option<String>,
+    /// The base64 encoded SHA-256 hash of the uploaded bytes.
+    pub sha256_hash: Option<String>,
+    /// The URI to reference the file by in a [`FileData`] part.
+    pub uri: Option<String>,
+    pub state: Option<FileState>,
+    /// How the file was created, e.g. `UPLOADED`.
+    pub source: Option<String>,
+    /// Why processing failed, if `state` is `FAILED`.
+    pub error: Option<crate::error::ApiError>,
+}
+
+impl File {
+    /// A [`FileData`] referencing this file, if the server has assigned it a URI.
+    pub fn file_data(&self) -> Option<FileData> {
+        Some(FileData {
+            file_uri: self.uri.clone()?,
+            mime_type: self.mime_type.clone().unwrap_or_default(),
+        })
+    }
+}
+
+/// A page of results from `files.list`.
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ListFilesResponse {
+    #[serde(default)]
+    pub files: Vec<File>,
+    /// A token for the next page, absent on the last page.
+    pub next_page_token: Option<String>,
+}
+
+/// Content cached on the server so it can be reused across generation requests.
+///
+/// Used both to create a cache and as the handle returned by the server.
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Setters, Default)]
+#[serde(rename_all = "camelCase")]
+#[setters(strip_option, into)]
+pub struct CachedContent {
+    /// The resource name of the cache, e.g. `cachedContents/abc-123`. Set by the server.
+    pub name: Option<String>,
+    /// The human-readable name of the cache.
+    pub display_name: Option<String>,
+    /// The model the cache is for, e.g. `gemini-2.0-flash`.
+    pub model: Option<String>,
+    /// Instructions for the model to steer it toward better performance.
     pub system_instruction: Option<Content>,
+    /// The content to cache.
+    pub contents: Option<Vec<Content>>,
+    /// Tools the model may use to generate the next response.
+    pub tools: Option<Vec<Tool>>,
+    /// Configuration for any tools specified in `tools`.
+    pub tool_config: Option<ToolConfig>,
+    /// How long the cache lives after creation, as a protobuf duration, e.g. `"300s"`.
+    pub ttl: Option<String>,
+    /// When the cache expires, as an RFC 3339 timestamp. Exclusive with `ttl`.
+    pub expire_time: Option<String>,
+    pub create_time: Option<String>,
+    pub update_time: Option<String>,
+    /// Token usage of the cached content. Set by the server.
+    pub usage_metadata: Option<CachedContentUsageMetadata>,
+}
+
+impl CachedContent {
+    /// Sets the time to live of the cache.
+    pub fn ttl_duration(self, ttl: std::time::Duration) -> Self {
+        self.ttl(format!("{}s", ttl.as_secs_f64()))
+    }
+
+    /// Starts a generation request that uses this cache as context.
+    ///
+    /// The request inherits the cache's model. The system instruction, tools and tool
+    /// config live in the cache and must not be set on the request.
+    pub fn request(&self) -> GenerateContentReq {
+        let model = self.model.as_deref().unwrap_or_default();
+        // Vertex AI returns the full publisher model path; only the model ID is needed.
+        let model = model.rsplit_once("models/").map_or(model, |(_, m)| m);
+        GenerateContentReq {
+            model: model.to_string(),
+            cached_content: self.name.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Token usage of cached content.
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedContentUsageMetadata {
+    /// The number of tokens the cached content consumes.
+    pub total_token_count: Option<i64>,
+    /// Vertex AI only.
+    pub text_count: Option<i64>,
+    /// Vertex AI only.
+    pub image_count: Option<i64>,
+    /// Vertex AI only.
+    pub video_duration_seconds: Option<i64>,
+    /// Vertex AI only.
+    pub audio_duration_seconds: Option<i64>,
+}
+
+/// A page of results from `cachedContents.list`.
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ListCachedContentsResponse {
+    #[serde(default)]
+    pub cached_contents: Vec<CachedContent>,
+    /// A token for the next page, absent on the last page.
+    pub next_page_token: Option<String>,
+}
+
+/// (De)serializes dates in the `google.type.Date` wire format, `{"year", "month", "day"}`.
+mod google_date {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use time::{Date, Month};
+
+    #[derive(Serialize, Deserialize)]
+    struct GoogleDate {
+        #[serde(default)]
+        year: i32,
+        #[serde(default)]
+        month: u8,
+        #[serde(default)]
+        day: u8,
+    }
+
+    pub fn serialize<S: Serializer>(date: &Option<Date>, s: S) -> Result<S::Ok, S::Error> {
+        date.map(|d| GoogleDate {
+            year: d.year(),
+            month: d.month().into(),
+            day: d.day(),
+        })
+        .serialize(s)
+    }
+
+    /// Partial dates (a zero month or day) are mapped to the first month or day.
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Date>, D::Error> {
+        let Some(g) = Option::<GoogleDate>::deserialize(d)? else {
+            return Ok(None);
+        };
+        let month = Month::try_from(g.month.max(1)).map_err(serde::de::Error::custom)?;
+        Date::from_calendar_date(g.year, month, g.day.max(1))
+            .map(Some)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// (De)serializes bytes as a base64 string. Standard and URL-safe alphabets are both
+/// accepted when decoding.
+mod base64_bytes {
+    use base64::engine::general_purpose::{STANDARD, URL_SAFE};
+    use base64::Engine;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(data: &[u8], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&STANDARD.encode(data))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(d)?;
+        decode(&encoded).map_err(serde::de::Error::custom)
+    }
+
+    pub(super) fn decode(encoded: &str) -> Result<Vec<u8>, base64::DecodeError> {
+        STANDARD
+            .decode(encoded)
+            .or_else(|_| URL_SAFE.decode(encoded))
+    }
+}
+
+/// Like `base64_bytes`, for optional fields.
+mod base64_bytes_opt {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(data: &Option<Vec<u8>>, s: S) -> Result<S::Ok, S::Error> {
+        match data {
+            Some(d) => s.serialize_str(&STANDARD.encode(d)),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Vec<u8>>, D::Error> {
+        Option::<String>::deserialize(d)?
+            .map(|e| super::base64_bytes::decode(&e).map_err(serde::de::Error::custom))
+            .transpose()
+    }
 }
@@ -1,6 +1,12 @@
 use std::collections::HashMap;
+use std::time::Duration;
+
+use serde_derive::{Deserialize, Serialize};
 use thiserror;
 
+use crate::datatypes::{BlockedReason, FinishReason, SafetyRating};
+use crate::retry::DEFAULT_RETRYABLE_STATUSES;
+
 pub type Result<T> = std::result::Result<T, GenAiError>;
 
 /// Error variants returned by the Google GenAI API client.
@@ -11,8 +17,93 @@ pub enum GenAiError {
     #[error("Remote error {status}: {message}")]
     Remote {
         status: u16,
+        /// The error message from the body, or the raw body if it could not be parsed.
         message: String,
         headers: HashMap<String, String>,
+        /// The raw response body.
+        body: String,
+        /// The parsed `google.rpc.Status`, if the body contained one.
+        error: Option<Box<ApiError>>,
+    },
+
+    /// The server failed to process an uploaded file.
+    #[error(
+        "Processing of {name} failed: {}",
+        .error.as_ref().and_then(|e| e.message.as_deref()).unwrap_or("unknown error")
+    )]
+    FileFailed {
+        /// The name of the file, e.g. `files/abc-123`.
+        name: String,
+        /// Why processing failed, if the server said.
+        error: Option<Box<ApiError>>,
+    },
+
+    /// The request could not be sent, or the response could not be read.
+    #[error("Transport error")]
+    Transport(#[source] reqwest::Error),
+
+    /// A response body was not the JSON that was expected.
+    #[error("Failed to decode response")]
+    Decode {
+        /// The raw body.
+        body: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// A response stream broke the server-sent events protocol.
+    #[error("Stream error: {message}")]
+    Stream {
+        message: String,
+        #[source]
+        source: Option<Box<dyn std::error::Error + Send + Sync>>,
+    },
+
+    /// A response stream ended before the response was complete.
+    #[error("Stream ended before the response was complete")]
+    StreamTruncated,
+
+    /// The request was rejected by the client before it was sent.
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    /// The prompt or the response was blocked, for example by safety filters, or the
+    /// response had no candidates.
+    #[error("{}", describe_block(.block_reason, .finish_reason))]
+    Blocked {
+        /// Why the prompt was blocked, if it was.
+        block_reason: Option<BlockedReason>,
+        /// Why the candidate stopped, if the response was blocked.
+        finish_reason: Option<FinishReason>,
+        /// A human-readable explanation, if the server gave one.
+        message: Option<String>,
+        /// The safety ratings of the prompt or the candidate.
+        safety_ratings: Vec<SafetyRating>,
+    },
+
+    /// The request did not complete within its timeout or deadline.
+    #[error("Request timed out")]
+    Timeout,
+
+    /// The request was cancelled through its cancellation token.
+    #[error("Request was cancelled")]
+    Cancelled,
+
+    /// Credentials could not be loaded or obtained.
+    #[error("Authentication error: {message}")]
+    Auth {
+        message: String,
+        #[source]
+        source: Option<Box<dyn std::error::Error + Send + Sync>>,
+    },
+
+    /// The model's output could not be parsed into the requested type.
+    #[error("Failed to parse model output")]
+    ParseOutput {
+        /// The text the model produced.
+        text: String,
+        #[source]
+        source: serde_json::Error,
     },
 
     /// Internal client errors.
@@ -20,7 +111,103 @@ pub enum GenAiError {
     Internal(String),
 }
 
+/// A coarse classification of a [`GenAiError`], for deciding how to handle it without
+/// matching on messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The server returned an error status other than 401 or 403, or failed to process
+    /// a file.
+    Remote,
+    /// The connection failed or broke.
+    Transport,
+    /// A response or the model's output could not be decoded.
+    Decode,
+    /// A response stream was malformed or cut short.
+    Stream,
+    /// The request was rejected before it was sent.
+    InvalidRequest,
+    /// The prompt or response was blocked.
+    Blocked,
+    /// The request ran out of time.
+    Timeout,
+    /// The request was cancelled.
+    Cancelled,
+    /// Credentials were missing, invalid or rejected, including 401 and 403 responses.
+    Auth,
+    /// Anything else.
+    Internal,
+}
+
 impl GenAiError {
+    /// Builds an `Auth` error without a source.
+    pub(crate) fn auth(message: impl Into<String>) -> Self {
+        GenAiError::Auth {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Builds an `Auth` error caused by `source`.
+    pub(crate) fn auth_source(
+        message: impl Into<String>,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        GenAiError::Auth {
+            message: message.into(),
+            source: Some(source.into()),
+        }
+    }
+
+    /// Builds a `Decode` error for a response that parsed but didn't hold what was expected.
+    pub(crate) fn unexpected(body: String, message: impl std::fmt::Display) -> Self {
+        GenAiError::Decode {
+            body,
+            source: serde::de::Error::custom(message),
+        }
+    }
+
+    /// Converts a `reqwest` error: timeouts become `Timeout`, anything else `Transport`
+    /// with the URL dropped so it can't leak into logs.
+    pub(crate) fn transport(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            GenAiError::Timeout
+        } else {
+            GenAiError::Transport(e.without_url())
+        }
+    }
+
+    /// Classifies the error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            GenAiError::Remote {
+                status: 401 | 403, ..
+            } => ErrorKind::Auth,
+            GenAiError::Remote { .. } | GenAiError::FileFailed { .. } => ErrorKind::Remote,
+            GenAiError::Transport(_) => ErrorKind::Transport,
+            GenAiError::Decode { .. } | GenAiError::ParseOutput { .. } => ErrorKind::Decode,
+            GenAiError::Stream { .. } | GenAiError::StreamTruncated => ErrorKind::Stream,
+            GenAiError::InvalidRequest(_) => ErrorKind::InvalidRequest,
+            GenAiError::Blocked { .. } => ErrorKind::Blocked,
+            GenAiError::Timeout => ErrorKind::Timeout,
+            GenAiError::Cancelled => ErrorKind::Cancelled,
+            GenAiError::Auth { .. } => ErrorKind::Auth,
+            GenAiError::Internal(_) => ErrorKind::Internal,
+        }
+    }
+
+    /// Whether the same request might succeed if sent again.
+    ///
+    /// True for timeouts, failures to connect, truncated streams and the statuses the
+    /// default [`RetryPolicy`](crate::RetryPolicy) retries.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            GenAiError::Remote { status, .. } => DEFAULT_RETRYABLE_STATUSES.contains(status),
+            GenAiError::Transport(e) => e.is_connect(),
+            GenAiError::StreamTruncated | GenAiError::Timeout => true,
+            _ => false,
+        }
+    }
+
     pub async fn from_response(response: reqwest::Response) -> Self {
         let status = response.status().as_u16();
         let headers = response
@@ -33,11 +220,336 @@ impl GenAiError {
                 )
             })
             .collect();
-        let message = response.text().await.unwrap_or_default();
+        let body = response.text().await.unwrap_or_default();
+        Self::remote(status, headers, body)
+    }
+
+    /// Builds a `Remote` error from a status, headers and raw body, parsing the body if
+    /// possible.
+    pub fn remote(status: u16, headers: HashMap<String, String>, body: String) -> Self {
+        let error = ApiError::parse(&body).map(Box::new);
+        let message = error
+            .as_ref()
+            .and_then(|e| e.message.clone())
+            .unwrap_or_else(|| body.clone());
         GenAiError::Remote {
             status,
             message,
             headers,
+            body,
+            error,
+        }
+    }
+
+    /// The parsed API error, for `Remote` errors with a structured body and failed files.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            GenAiError::Remote { error, .. } | GenAiError::FileFailed { error, .. } => {
+                error.as_deref()
+            }
+            _ => None,
+        }
+    }
+
+    /// The canonical status name, e.g. `RESOURCE_EXHAUSTED`.
+    pub fn rpc_status(&self) -> Option<&str> {
+        self.api_error().and_then(|e| e.status.as_deref())
+    }
+
+    /// The delay the server asked us to wait before retrying, from `RetryInfo`.
+    pub fn retry_delay(&self) -> Option<Duration> {
+        self.api_error().and_then(ApiError::retry_delay)
+    }
+
+    /// Replaces every occurrence of `secret` in the error's text with a placeholder, so the
+    /// error can be safely logged. A source error whose text contains `secret` is dropped.
+    pub fn redact(self, secret: &str) -> Self {
+        if secret.is_empty() {
+            return self;
+        }
+        let scrub = |s: String| s.replace(secret, crate::client::REDACTED);
+        match self {
+            GenAiError::Remote {
+                status,
+                message,
+                headers,
+                body,
+                error,
+            } => GenAiError::Remote {
+                status,
+                message: scrub(message),
+                headers: headers.into_iter().map(|(k, v)| (k, scrub(v))).collect(),
+                body: scrub(body),
+                error: error.and_then(|e| scrub_api_error(&e, &scrub)),
+            },
+            GenAiError::FileFailed { name, error } => GenAiError::FileFailed {
+                name: scrub(name),
+                error: error.and_then(|e| scrub_api_error(&e, &scrub)),
+            },
+            GenAiError::ParseOutput { text, source } => GenAiError::ParseOutput {
+                text: scrub(text),
+                source,
+            },
+            GenAiError::Decode { body, source } => GenAiError::Decode {
+                body: scrub(body),
+                source,
+            },
+            GenAiError::Stream { message, source } => GenAiError::Stream {
+                message: scrub(message),
+                source: source.filter(|s| !s.to_string().contains(secret)),
+            },
+            GenAiError::InvalidRequest(m) => GenAiError::InvalidRequest(scrub(m)),
+            GenAiError::Blocked {
+                block_reason,
+                finish_reason,
+                message,
+                safety_ratings,
+            } => GenAiError::Blocked {
+                block_reason,
+                finish_reason,
+                message: message.map(scrub),
+                safety_ratings,
+            },
+            GenAiError::Auth { message, source } => GenAiError::Auth {
+                message: scrub(message),
+                source: source.filter(|s| !s.to_string().contains(secret)),
+            },
+            GenAiError::Internal(m) => GenAiError::Internal(scrub(m)),
+            // The remaining variants carry no text of ours, and transport errors have had
+            // their URLs removed.
+            e @ (GenAiError::Transport(_)
+            | GenAiError::StreamTruncated
+            | GenAiError::Timeout
+            | GenAiError::Cancelled) => e,
         }
     }
 }
+
+/// Scrubs every string of an API error, as secrets can be echoed anywhere in its details.
+fn scrub_api_error(error: &ApiError, scrub: &impl Fn(String) -> String) -> Option<Box<ApiError>> {
+    let mut value = serde_json::to_value(error).ok()?;
+    scrub_strings(&mut value, scrub);
+    serde_json::from_value(value).ok()
+}
+
+/// Applies `scrub` to every string in a JSON value.
+fn scrub_strings(value: &mut serde_json::Value, scrub: &impl Fn(String) -> String) {
+    match value {
+        serde_json::Value::String(s) => *s = scrub(std::mem::take(s)),
+        serde_json::Value::Array(items) => items.iter_mut().for_each(|v| scrub_strings(v, scrub)),
+        serde_json::Value::Object(map) => map.values_mut().for_each(|v| scrub_strings(v, scrub)),
+        _ => {}
+    }
+}
+
+fn describe_block(block: &Option<BlockedReason>, finish: &Option<FinishReason>) -> String {
+    match (block, finish) {
+        (Some(reason), _) => format!("Prompt blocked: {}", reason),
+        (None, Some(reason)) => format!("Response blocked: {}", reason),
+        (None, None) => "Response has no candidates".to_string(),
+    }
+}
+
+/// A `google.rpc.Status` error returned by the API.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiError {
+    /// The HTTP status code.
+    pub code: Option<i64>,
+    /// A developer-facing error message.
+    pub message: Option<String>,
+    /// The canonical status name, e.g. `RESOURCE_EXHAUSTED` or `INVALID_ARGUMENT`.
+    pub status: Option<String>,
+    /// Typed error details.
+    #[serde(default)]
+    pub details: Vec<ErrorDetail>,
+}
+
+impl ApiError {
+    /// Parses an error body of the form `{"error": {...}}`. Streaming endpoints wrap
+    /// the object in an array, which is accepted too.
+    pub fn parse(body: &str) -> Option<Self> {
+        #[derive(Deserialize)]
+        struct Envelope {
+            error: ApiError,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Body {
+            One(Envelope),
+            Many(Vec<Envelope>),
+        }
+
+        match serde_json::from_str(body).ok()? {
+            Body::One(e) => Some(e.error),
+            Body::Many(v) => v.into_iter().next().map(|e| e.error),
+        }
+    }
+
+    /// The `RetryInfo` detail, if present.
+    pub fn retry_info(&self) -> Option<&RetryInfo> {
+        self.details.iter().find_map(|d| match d {
+            ErrorDetail::RetryInfo(r) => Some(r),
+            _ => None,
+        })
+    }
+
+    /// The `ErrorInfo` detail, if present.
+    pub fn error_info(&self) -> Option<&ErrorInfo> {
+        self.details.iter().find_map(|d| match d {
+            ErrorDetail::ErrorInfo(r) => Some(r),
+            _ => None,
+        })
+    }
+
+    /// The `QuotaFailure` detail, if present.
+    pub fn quota_failure(&self) -> Option<&QuotaFailure> {
+        self.details.iter().find_map(|d| match d {
+            ErrorDetail::QuotaFailure(r) => Some(r),
+            _ => None,
+        })
+    }
+
+    /// The `BadRequest` detail, if present.
+    pub fn bad_request(&self) -> Option<&BadRequest> {
+        self.details.iter().find_map(|d| match d {
+            ErrorDetail::BadRequest(r) => Some(r),
+            _ => None,
+        })
+    }
+
+    /// The retry delay requested by the server.
+    pub fn retry_delay(&self) -> Option<Duration> {
+        self.retry_info().and_then(RetryInfo::delay)
+    }
+}
+
+const RETRY_INFO_TYPE: &str = "type.googleapis.com/google.rpc.RetryInfo";
+const QUOTA_FAILURE_TYPE: &str = "type.googleapis.com/google.rpc.QuotaFailure";
+const ERROR_INFO_TYPE: &str = "type.googleapis.com/google.rpc.ErrorInfo";
+const BAD_REQUEST_TYPE: &str = "type.googleapis.com/google.rpc.BadRequest";
+
+/// A typed entry in `google.rpc.Status.details`, discriminated by `@type`.
+#[derive(Debug, Clone)]
+pub enum ErrorDetail {
+    RetryInfo(RetryInfo),
+    QuotaFailure(QuotaFailure),
+    ErrorInfo(ErrorInfo),
+    BadRequest(BadRequest),
+    /// A detail of a type this crate does not model, kept verbatim.
+    Other(serde_json::Value),
+}
+
+impl serde::Serialize for ErrorDetail {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        fn tagged<T: serde::Serialize>(ty: &str, v: &T) -> serde_json::Value {
+            let mut value = serde_json::to_value(v).unwrap_or_default();
+            if let Some(obj) = value.as_object_mut() {
+                obj.insert("@type".to_string(), ty.into());
+            }
+            value
+        }
+        let value = match self {
+            ErrorDetail::RetryInfo(v) => tagged(RETRY_INFO_TYPE, v),
+            ErrorDetail::QuotaFailure(v) => tagged(QUOTA_FAILURE_TYPE, v),
+            ErrorDetail::ErrorInfo(v) => tagged(ERROR_INFO_TYPE, v),
+            ErrorDetail::BadRequest(v) => tagged(BAD_REQUEST_TYPE, v),
+            ErrorDetail::Other(v) => v.clone(),
+        };
+        serde::Serialize::serialize(&value, serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ErrorDetail {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let value = <serde_json::Value as serde::Deserialize>::deserialize(deserializer)?;
+        let ty = value
+            .get("@type")
+            .and_then(|t| t.as_str())
+            .unwrap_or_default();
+        let detail = match ty {
+            RETRY_INFO_TYPE => serde_json::from_value(value.clone()).map(ErrorDetail::RetryInfo),
+            QUOTA_FAILURE_TYPE => {
+                serde_json::from_value(value.clone()).map(ErrorDetail::QuotaFailure)
+            }
+            ERROR_INFO_TYPE => serde_json::from_value(value.clone()).map(ErrorDetail::ErrorInfo),
+            BAD_REQUEST_TYPE => serde_json::from_value(value.clone()).map(ErrorDetail::BadRequest),
+            _ => return Ok(ErrorDetail::Other(value)),
+        };
+        // A malformed known detail shouldn't make the whole error unparseable.
+        Ok(detail.unwrap_or(ErrorDetail::Other(value)))
+    }
+}
+
+/// Describes when the client may retry a failed request.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryInfo {
+    /// The delay as a protobuf duration string, e.g. `"7s"` or `"0.5s"`.
+    pub retry_delay: Option<String>,
+}
+
+impl RetryInfo {
+    /// The parsed retry delay.
+    pub fn delay(&self) -> Option<Duration> {
+        let secs: f64 = self
+            .retry_delay
+            .as_deref()?
+            .strip_suffix('s')?
+            .parse()
+            .ok()?;
+        Duration::try_from_secs_f64(secs).ok()
+    }
+}
+
+/// Describes how a quota check failed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaFailure {
+    #[serde(default)]
+    pub violations: Vec<QuotaViolation>,
+}
+
+/// A single quota violation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaViolation {
+    pub subject: Option<String>,
+    pub description: Option<String>,
+    pub quota_metric: Option<String>,
+    pub quota_id: Option<String>,
+    pub quota_dimensions: Option<HashMap<String, String>>,
+    pub quota_value: Option<String>,
+}
+
+/// Describes the cause of an error with structured details.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorInfo {
+    pub reason: Option<String>,
+    pub domain: Option<String>,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+/// Describes violations in a client request.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BadRequest {
+    #[serde(default)]
+    pub field_violations: Vec<FieldViolation>,
+}
+
+/// A single bad request field.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldViolation {
+    pub field: Option<String>,
+    pub description: Option<String>,
+}
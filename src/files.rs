@@ -0,0 +1,371 @@
+//! The Files API, for media too large to send inline.
+//!
+//! Uploads use Google's resumable upload protocol: a session is started with the file's
+//! metadata, the bytes are then sent in chunks read from disk, and an interrupted session
+//! can be queried for the number of bytes the server received and continued from there.
+
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+use derive_setters::*;
+use futures_util::Stream;
+use reqwest::Method;
+use serde_derive::Deserialize;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use crate::client::{decode_json, paginate, Client};
+use crate::datatypes::{File, FileState, ListFilesResponse};
+use crate::error::*;
+
+/// The default number of bytes sent per upload request.
+pub const DEFAULT_UPLOAD_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Upload chunks other than the last must be a multiple of this size.
+pub const UPLOAD_CHUNK_GRANULARITY: usize = 256 * 1024;
+
+/// How many chunks in a row may fail before an upload is abandoned.
+const MAX_CHUNK_FAILURES: u32 = 3;
+
+/// How many leading bytes are read to detect a file's MIME type.
+const SNIFF_LEN: usize = 512;
+
+pub type FileStream = Pin<Box<dyn Stream<Item = Result<File>> + Send>>;
+
+/// Options for [`Client::upload_file`].
+#[derive(Debug, Clone, Setters)]
+#[setters(strip_option, into)]
+pub struct UploadFileConfig {
+    /// The human-readable name of the file.
+    pub display_name: Option<String>,
+    /// The MIME type of the file. Detected from its contents or extension when unset.
+    pub mime_type: Option<String>,
+    /// Bytes sent per request; must be a multiple of [`UPLOAD_CHUNK_GRANULARITY`].
+    pub chunk_size: usize,
+}
+
+impl Default for UploadFileConfig {
+    fn default() -> Self {
+        Self {
+            display_name: None,
+            mime_type: None,
+            chunk_size: DEFAULT_UPLOAD_CHUNK_SIZE,
+        }
+    }
+}
+
+/// An upload session started by [`Client::start_upload`].
+///
+/// The session URL stays valid for about a week, so it can be persisted and passed back to
+/// [`Client::resume_upload`] after the process is interrupted.
+#[derive(Debug, Clone)]
+pub struct ResumableUpload {
+    /// The session URL returned by the server.
+    pub upload_url: String,
+    /// The file being uploaded.
+    pub path: PathBuf,
+    /// The total size of the file in bytes.
+    pub size: u64,
+    /// Bytes sent per request.
+    pub chunk_size: usize,
+}
+
+/// The body returned when an upload is finalized.
+#[derive(Deserialize)]
+struct UploadResponse {
+    file: File,
+}
+
+impl Client {
+    /// Uploads a file, reading it from disk one chunk at a time.
+    ///
+    /// Failed chunks are retried from the offset the server reports. Only supported by the
+    /// Gemini Developer API.
+    pub async fn upload_file(
+        &self,
+        path: impl AsRef<Path>,
+        config: UploadFileConfig,
+    ) -> Result<File> {
+        let upload = self.start_upload(path, config).await?;
+        self.upload_from(&upload, 0).await
+    }
+
+    /// Starts a resumable upload session for a file without sending any of its bytes.
+    ///
+    /// Only supported by the Gemini Developer API.
+    pub async fn start_upload(
+        &self,
+        path: impl AsRef<Path>,
+        config: UploadFileConfig,
+    ) -> Result<ResumableUpload> {
+        self.require_gemini_api("files.upload")?;
+        if config.chunk_size == 0 || !config.chunk_size.is_multiple_of(UPLOAD_CHUNK_GRANULARITY) {
+            return Err(GenAiError::InvalidRequest(format!(
+                "Upload chunk size must be a positive multiple of {} bytes",
+                UPLOAD_CHUNK_GRANULARITY
+            )));
+        }
+        let path = path.as_ref().to_path_buf();
+        let size = file_size(&path).await?;
+        let mime_type = match config.mime_type {
+            Some(m) => m,
+            None => sniff_mime_type(&path).await?,
+        };
+
+        let url = format!("{}/files", self.upload_root());
+        let (rb, secret) = self.post(&url).await?;
+        let rb = rb
+            .header("X-Goog-Upload-Protocol", "resumable")
+            .header("X-Goog-Upload-Command", "start")
+            .header("X-Goog-Upload-Header-Content-Length", size)
+            .header("X-Goog-Upload-Header-Content-Type", &mime_type)
+            .json(&serde_json::json!({ "file": { "displayName": config.display_name } }));
+        let response = self.send(rb).await.map_err(|e| e.redact(&secret))?;
+        let upload_url = response
+            .headers()
+            .get("x-goog-upload-url")
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| GenAiError::unexpected(String::new(), "Upload session URL missing"))?
+            .to_string();
+        Ok(ResumableUpload {
+            upload_url,
+            path,
+            size,
+            chunk_size: config.chunk_size,
+        })
+    }
+
+    /// Continues an interrupted upload from the offset the server has received.
+    ///
+    /// Only supported by the Gemini Developer API.
+    pub async fn resume_upload(&self, upload: &ResumableUpload) -> Result<File> {
+        self.require_gemini_api("files.upload")?;
+        match self.query_upload(upload).await? {
+            UploadProgress::Complete(file) => Ok(*file),
+            UploadProgress::Received(offset) => self.upload_from(upload, offset).await,
+        }
+    }
+
+    /// Sends the bytes of `upload` from `offset` onwards, finalizing with the last chunk.
+    async fn upload_from(&self, upload: &ResumableUpload, mut offset: u64) -> Result<File> {
+        let mut file = open(&upload.path).await?;
+        if file_size(&upload.path).await? != upload.size {
+            return Err(GenAiError::InvalidRequest(format!(
+                "{} changed size during upload",
+                upload.path.display()
+            )));
+        }
+        let mut failures = 0;
+        loop {
+            let len = (upload.size - offset).min(upload.chunk_size as u64) as usize;
+            let mut chunk = vec![0; len];
+            file.seek(SeekFrom::Start(offset))
+                .await
+                .map_err(|e| read_error(&upload.path, e))?;
+            file.read_exact(&mut chunk)
+                .await
+                .map_err(|e| read_error(&upload.path, e))?;
+            let last = offset + len as u64 == upload.size;
+            let command = if last { "upload, finalize" } else { "upload" };
+
+            let (rb, secret) = self.post(&upload.upload_url).await?;
+            let rb = rb
+                .header("X-Goog-Upload-Command", command)
+                .header("X-Goog-Upload-Offset", offset)
+                .body(chunk);
+            let err = match self.send(rb).await {
+                Ok(response) if last => {
+                    return decode_json::<UploadResponse>(response)
+                        .await
+                        .map(|r| r.file);
+                }
+                Ok(_) => {
+                    offset += len as u64;
+                    failures = 0;
+                    continue;
+                }
+                Err(e) => e.redact(&secret),
+            };
+
+            // The server may have received part of the chunk; continue from wherever it
+            // says it is. Failed queries count against the same budget, and the chunk's
+            // error is the one reported.
+            failures += 1;
+            loop {
+                if failures >= MAX_CHUNK_FAILURES {
+                    return Err(err);
+                }
+                match self.query_upload(upload).await {
+                    Ok(UploadProgress::Complete(file)) => return Ok(*file),
+                    Ok(UploadProgress::Received(received)) => {
+                        offset = received;
+                        break;
+                    }
+                    Err(_) => failures += 1,
+                }
+            }
+        }
+    }
+
+    /// Asks the server how much of an upload it has received.
+    async fn query_upload(&self, upload: &ResumableUpload) -> Result<UploadProgress> {
+        let (rb, secret) = self.post(&upload.upload_url).await?;
+        let response = self
+            .send(rb.header("X-Goog-Upload-Command", "query"))
+            .await
+            .map_err(|e| e.redact(&secret))?;
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        if header("x-goog-upload-status").as_deref() == Some("final") {
+            let file = decode_json::<UploadResponse>(response).await?.file;
+            return Ok(UploadProgress::Complete(Box::new(file)));
+        }
+        let received = header("x-goog-upload-size-received")
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| GenAiError::unexpected(String::new(), "Upload size received missing"))?;
+        if received > upload.size {
+            return Err(GenAiError::unexpected(
+                String::new(),
+                format!(
+                    "Server received {} bytes of a {} byte upload",
+                    received, upload.size
+                ),
+            ));
+        }
+        Ok(UploadProgress::Received(received))
+    }
+
+    /// Fetches metadata about an uploaded file.
+    ///
+    /// Only supported by the Gemini Developer API.
+    pub async fn get_file(&self, name: &str) -> Result<File> {
+        self.require_gemini_api("files.get")?;
+        let url = format!("{}/{}", self.api_root(), qualified_file(name));
+        let (rb, secret) = self.request(Method::GET, &url).await?;
+        self.send_json(rb).await.map_err(|e| e.redact(&secret))
+    }
+
+    /// Fetches a single page of uploaded files.
+    ///
+    /// Only supported by the Gemini Developer API.
+    pub async fn list_files_page(
+        &self,
+        page_size: Option<u32>,
+        page_token: Option<&str>,
+    ) -> Result<ListFilesResponse> {
+        self.require_gemini_api("files.list")?;
+        let url = format!("{}/files", self.api_root());
+        let (mut rb, secret) = self.request(Method::GET, &url).await?;
+        if let Some(size) = page_size {
+            rb = rb.query(&[("pageSize", size)]);
+        }
+        if let Some(token) = page_token {
+            rb = rb.query(&[("pageToken", token)]);
+        }
+        self.send_json(rb).await.map_err(|e| e.redact(&secret))
+    }
+
+    /// Lists all uploaded files, fetching further pages as the stream is consumed.
+    ///
+    /// Only supported by the Gemini Developer API.
+    pub fn list_files(&self, page_size: Option<u32>) -> FileStream {
+        let client = self.clone();
+        paginate(move |token| {
+            let client = client.clone();
+            async move {
+                let page = client.list_files_page(page_size, token.as_deref()).await?;
+                Ok((page.files, page.next_page_token))
+            }
+        })
+    }
+
+    /// Deletes an uploaded file.
+    ///
+    /// Only supported by the Gemini Developer API.
+    pub async fn delete_file(&self, name: &str) -> Result<()> {
+        self.require_gemini_api("files.delete")?;
+        let url = format!("{}/{}", self.api_root(), qualified_file(name));
+        let (rb, secret) = self.request(Method::DELETE, &url).await?;
+        self.send(rb).await.map_err(|e| e.redact(&secret))?;
+        Ok(())
+    }
+
+    /// Polls a file every `interval` until the server has finished processing it.
+    ///
+    /// If processing fails, returns [`GenAiError::FileFailed`] carrying the file's error. Fails
+    /// with [`GenAiError::Timeout`] if the file is still not active after `timeout`. Only
+    /// supported by the Gemini Developer API.
+    pub async fn wait_for_file_active(
+        &self,
+        name: &str,
+        interval: Duration,
+        timeout: Duration,
+    ) -> Result<File> {
+        let start = Instant::now();
+        loop {
+            let file = self.get_file(name).await?;
+            match file.state {
+                Some(FileState::Active) => return Ok(file),
+                Some(FileState::Failed) => {
+                    return Err(GenAiError::FileFailed {
+                        name: file.name.unwrap_or_default(),
+                        error: file.error.map(Box::new),
+                    })
+                }
+                _ => {}
+            }
+            if start.elapsed() + interval > timeout {
+                return Err(GenAiError::Timeout);
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+}
+
+/// How far the server has got with an upload.
+enum UploadProgress {
+    /// The upload was finalized.
+    Complete(Box<File>),
+    /// The number of bytes persisted so far.
+    Received(u64),
+}
+
+/// Returns a file name in the `files/{id}` form.
+fn qualified_file(name: &str) -> String {
+    format!("files/{}", name.strip_prefix("files/").unwrap_or(name))
+}
+
+fn read_error(path: &Path, e: std::io::Error) -> GenAiError {
+    GenAiError::InvalidRequest(format!("Failed to read {}: {}", path.display(), e))
+}
+
+async fn open(path: &Path) -> Result<tokio::fs::File> {
+    tokio::fs::File::open(path)
+        .await
+        .map_err(|e| read_error(path, e))
+}
+
+async fn file_size(path: &Path) -> Result<u64> {
+    tokio::fs::metadata(path)
+        .await
+        .map(|m| m.len())
+        .map_err(|e| read_error(path, e))
+}
+
+/// Detects the MIME type of a file from its leading bytes or extension.
+async fn sniff_mime_type(path: &Path) -> Result<String> {
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    open(path)
+        .await?
+        .take(SNIFF_LEN as u64)
+        .read_to_end(&mut head)
+        .await
+        .map_err(|e| read_error(path, e))?;
+    Ok(crate::media::guess_mime_type(Some(path), &head).to_string())
+}
@@ -1,92 +1,54 @@
+pub mod auth;
+pub mod caches;
+pub mod chat;
+pub mod client;
 pub mod datatypes;
 pub mod error;
+pub mod files;
+pub mod media;
+pub mod options;
+pub mod retry;
+pub mod schema;
+pub mod stream;
+pub mod structured;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod tools;
 
-use error::*;
-use futures_util::Stream;
-use futures_util::StreamExt;
-use std::pin::Pin;
+pub use caches::CachedContentStream;
+pub use chat::{ChatSession, ChatStream};
+pub use client::{Backend, Client, ClientBuilder, ModelStream, ResponseStream};
+pub use files::{FileStream, ResumableUpload, UploadFileConfig};
+pub use options::RequestOptions;
+pub use retry::RetryPolicy;
+pub use schema::{tool, GenAiSchema};
+pub use stream::{StreamAccumulator, TextDeltaStream};
+pub use tokio_util::sync::CancellationToken;
+pub use tools::{ToolHooks, ToolLoopResult, ToolRegistry};
 
-const BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta/models";
+/// Support code for the derive macros. Not public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use serde_json;
 
-use reqwest_eventsource::{Event, RequestBuilderExt};
+    use crate::error::{GenAiError, Result};
 
-pub type ResponseStream =
-    Pin<Box<dyn Stream<Item = Result<datatypes::GenerateContentResponse>> + Send>>;
-
-/// Generates streaming content from the API.
-///
-/// Returns a stream of `GenerateContentResponse` objects that can be consumed asynchronously.
-/// Uses Server-Sent Events (SSE) to stream the responses.
-pub async fn generate_content_stream(
-    api_key: &str,
-    req: datatypes::GenerateContentReq,
-) -> Result<ResponseStream> {
-    let client = reqwest::Client::new();
-    let url = format!(
-        "{}/{}:streamGenerateContent?alt=sse&key={}",
-        BASE_URL, req.model, api_key
-    );
-    let es = match client.post(&url).json(&req).eventsource() {
-        Ok(es) => es,
-        Err(reqwest_eventsource::CannotCloneRequestError) => {
-            return Err(GenAiError::Internal("Failed to clone request".to_string()));
-        }
-    };
-    let stream = es.filter_map(|event| async move {
-        match event {
-            Ok(Event::Message(message)) => {
-                let data = message.data;
-                if data == "[DONE]" {
-                    return Some(Ok(datatypes::GenerateContentResponse {
-                        candidates: None,
-                        prompt_feedback: None,
-                        model_version: None,
-                        usage_metadata: None,
-                    }));
-                }
-                Some(serde_json::from_str(&data).map_err(|e| {
-                    GenAiError::Internal(format!("JSON parse error: {}\n{}", e, data))
-                }))
-            }
-            Ok(Event::Open) => None,
-            Err(reqwest_eventsource::Error::InvalidStatusCode(_, response)) => {
-                Some(Err(GenAiError::from_response(response).await))
-            }
-            Err(reqwest_eventsource::Error::StreamEnded) => {
-                Some(Ok(datatypes::GenerateContentResponse {
-                    candidates: None,
-                    prompt_feedback: None,
-                    model_version: None,
-                    usage_metadata: None,
-                }))
-            }
-            Err(e) => Some(Err(GenAiError::Internal(format!("Stream error: {}", e)))),
-        }
-    });
+    /// Deserializes the argument `name` of a function call. Missing arguments are read as
+    /// `null`, so that optional arguments may be omitted.
+    pub fn arg<T: serde::de::DeserializeOwned>(args: &serde_json::Value, name: &str) -> Result<T> {
+        let value = args.get(name).cloned().unwrap_or_default();
+        serde_json::from_value(value)
+            .map_err(|e| GenAiError::InvalidRequest(format!("Invalid argument `{}`: {}", name, e)))
+    }
 
-    Ok(Box::pin(stream))
-}
+    /// Converts the error of a failed tool call. The message reaches the model as is.
+    pub fn tool_error<E: std::fmt::Display>(e: E) -> GenAiError {
+        GenAiError::Internal(e.to_string())
+    }
 
-/// Generates content from the API in a single request.
-///
-/// Makes a single POST request to the API and returns the complete response.
-pub async fn generate_content(
-    api_key: &str,
-    req: datatypes::GenerateContentReq,
-) -> Result<datatypes::GenerateContentResponse> {
-    let client = reqwest::Client::new();
-    let url = format!("{}/{}:generateContent?key={}", BASE_URL, req.model, api_key);
-    let response = client
-        .post(&url)
-        .json(&req)
-        .send()
-        .await
-        .map_err(|e| GenAiError::Internal(format!("Request failed: {}", e)))?;
-    if !response.status().is_success() {
-        return Err(GenAiError::from_response(response).await);
+    /// Serializes the result of a tool call.
+    pub fn result<T: serde::Serialize>(output: T) -> Result<serde_json::Value> {
+        serde_json::to_value(output)
+            .map_err(|e| GenAiError::Internal(format!("Failed to serialize result: {}", e)))
     }
-    response
-        .json()
-        .await
-        .map_err(|e| GenAiError::Internal(format!("Failed to deserialize response: {}", e)))
 }
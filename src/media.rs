@@ -0,0 +1,197 @@
+//! MIME type detection for inline media.
+
+use std::path::Path;
+
+/// The MIME type used when nothing more specific can be detected.
+pub const DEFAULT_MIME_TYPE: &str = "application/octet-stream";
+
+/// Guesses the MIME type of `data`, preferring its magic bytes and falling back to the
+/// extension of `path`.
+pub fn guess_mime_type(path: Option<&Path>, data: &[u8]) -> &'static str {
+    sniff(data)
+        .or_else(|| path.and_then(from_extension))
+        .unwrap_or(DEFAULT_MIME_TYPE)
+}
+
+/// Detects well-known formats from their leading bytes.
+fn sniff(data: &[u8]) -> Option<&'static str> {
+    let starts = |magic: &[u8]| data.starts_with(magic);
+    let at = |offset: usize, magic: &[u8]| data.get(offset..offset + magic.len()) == Some(magic);
+    if starts(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if starts(b"\xff\xd8\xff") {
+        Some("image/jpeg")
+    } else if starts(b"GIF87a") || starts(b"GIF89a") {
+        Some("image/gif")
+    } else if starts(b"RIFF") && at(8, b"WEBP") {
+        Some("image/webp")
+    } else if starts(b"RIFF") && at(8, b"WAVE") {
+        Some("audio/wav")
+    } else if starts(b"%PDF-") {
+        Some("application/pdf")
+    } else if at(4, b"ftypheic") || at(4, b"ftypheix") {
+        Some("image/heic")
+    } else if at(4, b"ftypqt") {
+        Some("video/quicktime")
+    } else if at(4, b"ftypM4A") {
+        Some("audio/mp4")
+    } else if at(4, b"ftyp") {
+        Some("video/mp4")
+    } else if starts(b"\x1a\x45\xdf\xa3") {
+        Some("video/webm")
+    } else if starts(b"OggS") {
+        Some("audio/ogg")
+    } else if starts(b"fLaC") {
+        Some("audio/flac")
+    } else if starts(b"ID3") || starts(b"\xff\xfb") || starts(b"\xff\xf3") || starts(b"\xff\xf2") {
+        Some("audio/mpeg")
+    } else {
+        None
+    }
+}
+
+/// Maps a file extension to a MIME type.
+fn from_extension(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    Some(match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "heic" => "image/heic",
+        "heif" => "image/heif",
+        "pdf" => "application/pdf",
+        "wav" => "audio/wav",
+        "mp3" => "audio/mpeg",
+        "aac" => "audio/aac",
+        "ogg" => "audio/ogg",
+        "flac" => "audio/flac",
+        "aiff" | "aif" => "audio/aiff",
+        "m4a" => "audio/mp4",
+        "mp4" => "video/mp4",
+        "mpeg" | "mpg" => "video/mpeg",
+        "mov" => "video/quicktime",
+        "avi" => "video/x-msvideo",
+        "flv" => "video/x-flv",
+        "webm" => "video/webm",
+        "wmv" => "video/x-ms-wmv",
+        "3gp" | "3gpp" => "video/3gpp",
+        "txt" => "text/plain",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "csv" => "text/csv",
+        "md" => "text/markdown",
+        "xml" => "text/xml",
+        "rtf" => "text/rtf",
+        "js" => "text/javascript",
+        "py" => "text/x-python",
+        "json" => "application/json",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datatypes::{Blob, Part};
+
+    #[test]
+    fn magic_numbers() {
+        let cases: &[(&[u8], &str)] = &[
+            (b"\x89PNG\r\n\x1a\n\0\0", "image/png"),
+            (b"\xff\xd8\xff\xe0", "image/jpeg"),
+            (b"GIF87a", "image/gif"),
+            (b"GIF89a\x01\0", "image/gif"),
+            (b"RIFF\0\0\0\0WEBPVP8 ", "image/webp"),
+            (b"RIFF\0\0\0\0WAVEfmt ", "audio/wav"),
+            (b"%PDF-1.7\n", "application/pdf"),
+            (b"\0\0\0\x18ftypheic", "image/heic"),
+            (b"\0\0\0\x18ftypheix", "image/heic"),
+            (b"\0\0\0\x14ftypqt  ", "video/quicktime"),
+            (b"\0\0\0\x20ftypM4A ", "audio/mp4"),
+            (b"\0\0\0\x20ftypisom", "video/mp4"),
+            (b"\x1a\x45\xdf\xa3\x01", "video/webm"),
+            (b"OggS\0\x02", "audio/ogg"),
+            (b"fLaC\0\0\0\x22", "audio/flac"),
+            (b"ID3\x04\0", "audio/mpeg"),
+            (b"\xff\xfb\x90\x64", "audio/mpeg"),
+            (b"\xff\xf3\x90\x64", "audio/mpeg"),
+            (b"\xff\xf2\x90\x64", "audio/mpeg"),
+        ];
+        for (data, expected) in cases {
+            assert_eq!(guess_mime_type(None, data), *expected, "{:?}", data);
+        }
+    }
+
+    #[test]
+    fn unknown_bytes() {
+        assert_eq!(guess_mime_type(None, b""), DEFAULT_MIME_TYPE);
+        assert_eq!(guess_mime_type(None, b"hello world"), DEFAULT_MIME_TYPE);
+        // Too short to hold the subtype at offset 8.
+        assert_eq!(guess_mime_type(None, b"RIFF\0\0\0\0"), DEFAULT_MIME_TYPE);
+        assert_eq!(
+            guess_mime_type(Some(Path::new("data.unknown")), b"hello"),
+            DEFAULT_MIME_TYPE
+        );
+        assert_eq!(
+            guess_mime_type(Some(Path::new("README")), b"hello"),
+            DEFAULT_MIME_TYPE
+        );
+    }
+
+    #[test]
+    fn extension_fallback() {
+        let guess = |name: &str| guess_mime_type(Some(Path::new(name)), b"plain text");
+        assert_eq!(guess("notes.txt"), "text/plain");
+        assert_eq!(guess("photo.JPG"), "image/jpeg");
+        assert_eq!(guess("dir/clip.mov"), "video/quicktime");
+        assert_eq!(guess("script.py"), "text/x-python");
+        // Magic bytes win over the extension.
+        assert_eq!(
+            guess_mime_type(Some(Path::new("image.txt")), b"%PDF-1.4"),
+            "application/pdf"
+        );
+    }
+
+    #[test]
+    fn blob_from_path() {
+        let dir = std::env::temp_dir().join(format!("google-genai-media-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let png = dir.join("image.bin");
+        std::fs::write(&png, b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR").unwrap();
+        let csv = dir.join("table.csv");
+        std::fs::write(&csv, b"a,b\n1,2\n").unwrap();
+
+        let blob = Blob::from_path(&png).unwrap();
+        assert_eq!(blob.mime_type, "image/png");
+        assert_eq!(blob.data.len(), 16);
+        let blob = Blob::from_path(&csv).unwrap();
+        assert_eq!(blob.mime_type, "text/csv");
+        assert_eq!(blob.data, b"a,b\n1,2\n");
+
+        let err = Blob::from_path(dir.join("missing.png")).unwrap_err();
+        assert_eq!(err.kind(), crate::error::ErrorKind::InvalidRequest);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn inline_data_is_base64_on_the_wire() {
+        let part = Part::default().inline_data(Blob::new(DEFAULT_MIME_TYPE, vec![1, 2, 3]));
+        let json = serde_json::to_value(&part).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "inlineData": { "data": "AQID", "mimeType": DEFAULT_MIME_TYPE }
+            })
+        );
+        let back: Part = serde_json::from_value(json).unwrap();
+        assert_eq!(back.inline_data.unwrap().data, vec![1, 2, 3]);
+
+        // URL-safe base64 is accepted too.
+        let part: Part = serde_json::from_str(
+            r#"{"inlineData": {"data": "-_8=", "mimeType": "application/octet-stream"}}"#,
+        )
+        .unwrap();
+        assert_eq!(part.inline_data.unwrap().data, vec![0xfb, 0xff]);
+    }
+}
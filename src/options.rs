@@ -0,0 +1,71 @@
+//! Per-request limits for generation calls.
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use derive_setters::*;
+use tokio_util::sync::CancellationToken;
+
+use crate::error::*;
+
+/// Bounds how long a single generation call may take, and lets it be cancelled.
+///
+/// For streams, `timeout` and `deadline` cover the whole stream, from sending the request
+/// to receiving the last chunk, while `idle_timeout` bounds the wait for each chunk. A call
+/// that runs out of time fails with [`GenAiError::Timeout`], and one that is cancelled with
+/// [`GenAiError::Cancelled`]. Either way the underlying HTTP request is dropped, which
+/// closes its connection.
+#[derive(Debug, Clone, Default, Setters)]
+#[setters(strip_option, into)]
+pub struct RequestOptions {
+    /// Time allowed for the call, including retries, measured from when it starts.
+    pub timeout: Option<Duration>,
+    /// Point in time by which the call must be complete.
+    pub deadline: Option<Instant>,
+    /// For streams, the longest wait for the next chunk.
+    pub idle_timeout: Option<Duration>,
+    /// Cancels the call when triggered.
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl RequestOptions {
+    /// The deadline of a call that starts now: the earlier of `deadline` and `timeout` from
+    /// now.
+    pub(crate) fn effective_deadline(&self) -> Option<Instant> {
+        let timeout = self.timeout.map(|t| Instant::now() + t);
+        earliest(self.deadline, timeout)
+    }
+}
+
+/// The earlier of two optional instants.
+pub(crate) fn earliest(a: Option<Instant>, b: Option<Instant>) -> Option<Instant> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Runs `fut` until it completes, `deadline` passes or `cancel` is triggered. In the latter
+/// cases `fut` is dropped.
+pub(crate) async fn guard<F: Future>(
+    cancel: Option<&CancellationToken>,
+    deadline: Option<Instant>,
+    fut: F,
+) -> Result<F::Output> {
+    let timed = async {
+        match deadline {
+            Some(d) => tokio::time::timeout_at(d.into(), fut)
+                .await
+                .map_err(|_| GenAiError::Timeout),
+            None => Ok(fut.await),
+        }
+    };
+    match cancel {
+        Some(token) => tokio::select! {
+            biased;
+            _ = token.cancelled() => Err(GenAiError::Cancelled),
+            output = timed => output,
+        },
+        None => timed.await,
+    }
+}
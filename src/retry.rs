@@ -0,0 +1,161 @@
+//! Retry policy for transient failures.
+
+use std::time::Duration;
+
+use derive_setters::*;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use time::format_description::well_known::Rfc2822;
+use time::OffsetDateTime;
+
+/// The HTTP statuses that are retried by default: timeouts, rate limits and server errors.
+pub const DEFAULT_RETRYABLE_STATUSES: &[u16] = &[408, 429, 500, 502, 503, 504];
+
+/// Controls how a [`Client`](crate::Client) retries failed requests.
+///
+/// Requests are retried when the server responds with one of
+/// `retryable_statuses`, or when the connection fails before a response is
+/// received. The delay before each retry grows exponentially from
+/// `base_delay` up to `max_delay`, randomised by `jitter`. A server-supplied
+/// `Retry-After` header or `RetryInfo.retryDelay` takes precedence over the
+/// computed backoff; if it asks for a longer wait than `max_delay`, the error is
+/// returned instead of retrying.
+#[derive(Debug, Clone, Setters)]
+#[setters(strip_option, into)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first.
+    pub max_attempts: u32,
+    /// Delay before the first retry.
+    pub base_delay: Duration,
+    /// Upper bound for the delay before a retry, computed or requested by the server.
+    pub max_delay: Duration,
+    /// Fraction of each computed delay that is randomised, from 0.0 to 1.0.
+    pub jitter: f64,
+    /// HTTP status codes that are retried. Defaults to [`DEFAULT_RETRYABLE_STATUSES`].
+    pub retryable_statuses: Vec<u16>,
+    /// Give up once this much time has passed since the first attempt.
+    pub deadline: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            jitter: 0.2,
+            retryable_statuses: DEFAULT_RETRYABLE_STATUSES.to_vec(),
+            deadline: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self::default().max_attempts(1u32)
+    }
+
+    /// Whether a response with this status should be retried.
+    pub fn is_retryable_status(&self, status: u16) -> bool {
+        self.retryable_statuses.contains(&status)
+    }
+
+    /// The computed backoff before retry number `retry` (starting at 1).
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)));
+        let delay = exp.min(self.max_delay);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        delay.mul_f64(1.0 - jitter * fastrand::f64())
+    }
+
+    /// The delay before the next attempt, or `None` if no further attempts should be made.
+    ///
+    /// `attempt` is the number of attempts made so far, `elapsed` the time since the first
+    /// attempt started, and `hint` any delay requested by the server. A hint longer than
+    /// `max_delay` ends the retries, as retrying sooner would only be rejected again.
+    pub(crate) fn next_delay(
+        &self,
+        attempt: u32,
+        elapsed: Duration,
+        hint: Option<Duration>,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        let delay = match hint {
+            Some(hint) if hint > self.max_delay => return None,
+            Some(hint) => hint,
+            None => self.backoff(attempt),
+        };
+        if let Some(deadline) = self.deadline {
+            if elapsed + delay >= deadline {
+                return None;
+            }
+        }
+        Some(delay)
+    }
+}
+
+/// Parses a `Retry-After` header, given either as delay seconds or an HTTP date.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = OffsetDateTime::parse(value, &Rfc2822).ok()?;
+    let delta = at - OffsetDateTime::now_utc();
+    Some(delta.try_into().unwrap_or(Duration::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy::default()
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_secs(2))
+            .jitter(0.0)
+    }
+
+    #[test]
+    fn backoff_grows_up_to_max_delay() {
+        let policy = policy();
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(10), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn server_hints_are_bounded_by_max_delay() {
+        let policy = policy();
+        let hint = |secs| policy.next_delay(1, Duration::ZERO, Some(Duration::from_secs(secs)));
+        assert_eq!(hint(1), Some(Duration::from_secs(1)));
+        assert_eq!(hint(2), Some(Duration::from_secs(2)));
+        assert_eq!(hint(3600), None);
+    }
+
+    #[test]
+    fn attempts_and_deadline_end_retries() {
+        let policy = policy().max_attempts(3u32).deadline(Duration::from_secs(1));
+        assert!(policy.next_delay(2, Duration::ZERO, None).is_some());
+        assert_eq!(policy.next_delay(3, Duration::ZERO, None), None);
+        assert_eq!(policy.next_delay(1, Duration::from_millis(950), None), None);
+    }
+
+    #[test]
+    fn retry_after_accepts_seconds_and_dates() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, "7".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+        headers.insert(
+            RETRY_AFTER,
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+        headers.insert(RETRY_AFTER, "soon".parse().unwrap());
+        assert_eq!(retry_after(&headers), None);
+    }
+}
@@ -0,0 +1,91 @@
+//! Deriving [`Schema`]s from Rust types.
+//!
+//! [`GenAiSchema`] is implemented for primitives and standard containers, and can be
+//! derived for structs and enums with `#[derive(GenAiSchema)]`. The derive honours doc
+//! comments and serde's `rename`, `rename_all`, `skip` and `default` attributes. The
+//! [`tool`] attribute builds a [`FunctionDeclaration`](crate::datatypes::FunctionDeclaration) and a
+//! dispatcher from an async fn.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::datatypes::{Schema, Type};
+
+pub use google_genai_derive::{tool, GenAiSchema};
+
+/// A type whose JSON representation can be described by a [`Schema`].
+pub trait GenAiSchema {
+    /// The schema of the type's JSON representation.
+    fn schema() -> Schema;
+}
+
+macro_rules! impl_schema {
+    ($schema_type:ident, $format:expr, $($ty:ty),*) => {
+        $(impl GenAiSchema for $ty {
+            fn schema() -> Schema {
+                let schema = Schema::new(Type::$schema_type);
+                match $format {
+                    Some(format) => schema.format(format),
+                    None => schema,
+                }
+            }
+        })*
+    };
+}
+
+impl_schema!(String, None::<&str>, String, str, char);
+impl_schema!(Boolean, None::<&str>, bool);
+impl_schema!(Integer, Some("int32"), i8, i16, i32, u8, u16, u32);
+impl_schema!(Integer, Some("int64"), i64, u64, isize, usize);
+impl_schema!(Number, Some("float"), f32);
+impl_schema!(Number, Some("double"), f64);
+
+impl<T: GenAiSchema + ?Sized> GenAiSchema for &T {
+    fn schema() -> Schema {
+        T::schema()
+    }
+}
+
+impl<T: GenAiSchema + ?Sized> GenAiSchema for Box<T> {
+    fn schema() -> Schema {
+        T::schema()
+    }
+}
+
+impl<T: GenAiSchema> GenAiSchema for Option<T> {
+    fn schema() -> Schema {
+        T::schema().nullable(true)
+    }
+}
+
+impl<T: GenAiSchema> GenAiSchema for Vec<T> {
+    fn schema() -> Schema {
+        Schema::new(Type::Array).items(Box::new(T::schema()))
+    }
+}
+
+impl<T: GenAiSchema> GenAiSchema for [T] {
+    fn schema() -> Schema {
+        Vec::<T>::schema()
+    }
+}
+
+impl<T: GenAiSchema, const N: usize> GenAiSchema for [T; N] {
+    fn schema() -> Schema {
+        Vec::<T>::schema()
+            .min_items(N.to_string())
+            .max_items(N.to_string())
+    }
+}
+
+// Maps have no fixed properties, which the API can only describe as a bare object.
+impl<K, V> GenAiSchema for HashMap<K, V> {
+    fn schema() -> Schema {
+        Schema::new(Type::Object)
+    }
+}
+
+impl<K, V> GenAiSchema for BTreeMap<K, V> {
+    fn schema() -> Schema {
+        Schema::new(Type::Object)
+    }
+}
@@ -0,0 +1,236 @@
+//! Helpers for consuming streamed responses.
+
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use futures_util::{Stream, StreamExt};
+
+use crate::client::ResponseStream;
+use crate::datatypes::{
+    Candidate, CitationMetadata, Content, GenerateContentResponse, GroundingMetadata,
+    LogprobsResult, Part,
+};
+use crate::error::*;
+
+/// Folds the chunks of a streamed response into a single complete response.
+///
+/// Candidates are merged by index. Adjacent text parts are concatenated, keeping thoughts
+/// apart from the answer, while other parts, such as function calls, are kept whole.
+/// Citations, grounding and logprobs are merged, and for everything else the last value
+/// received wins, so the result carries the finish reason and usage metadata that arrive
+/// on the final chunks.
+#[derive(Debug, Clone, Default)]
+pub struct StreamAccumulator {
+    response: GenerateContentResponse,
+}
+
+impl StreamAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Consumes a stream to the end and returns the merged response.
+    pub async fn collect(mut stream: ResponseStream) -> Result<GenerateContentResponse> {
+        let mut acc = Self::new();
+        while let Some(chunk) = stream.next().await {
+            acc.push(&chunk?);
+        }
+        Ok(acc.finish())
+    }
+
+    /// Merges a chunk into the response.
+    pub fn push(&mut self, chunk: &GenerateContentResponse) {
+        let response = &mut self.response;
+        last(&mut response.response_id, &chunk.response_id);
+        last(&mut response.create_time, &chunk.create_time);
+        last(&mut response.model_version, &chunk.model_version);
+        last(&mut response.prompt_feedback, &chunk.prompt_feedback);
+        last(&mut response.usage_metadata, &chunk.usage_metadata);
+        for (i, candidate) in chunk.candidates.iter().flatten().enumerate() {
+            let index = candidate.index.unwrap_or(i as i64);
+            let candidates = response.candidates.get_or_insert_with(Vec::new);
+            let target = match candidates
+                .iter()
+                .position(|c| c.index.unwrap_or(0) == index)
+            {
+                Some(pos) => &mut candidates[pos],
+                None => {
+                    candidates.push(Candidate {
+                        index: Some(index),
+                        ..Default::default()
+                    });
+                    candidates.last_mut().expect("just pushed")
+                }
+            };
+            merge_candidate(target, candidate);
+        }
+    }
+
+    /// The response merged so far.
+    pub fn response(&self) -> &GenerateContentResponse {
+        &self.response
+    }
+
+    /// Returns the merged response.
+    pub fn finish(self) -> GenerateContentResponse {
+        self.response
+    }
+}
+
+fn merge_candidate(target: &mut Candidate, chunk: &Candidate) {
+    if let Some(content) = &chunk.content {
+        let merged = target.content.get_or_insert_with(Content::default);
+        last(&mut merged.role, &content.role);
+        for part in content.parts.iter().flatten() {
+            push_part(merged.parts.get_or_insert_with(Vec::new), part.clone());
+        }
+    }
+    if let Some(citations) = &chunk.citation_metadata {
+        let merged = target
+            .citation_metadata
+            .get_or_insert_with(CitationMetadata::default);
+        append(&mut merged.citations, &citations.citations);
+    }
+    if let Some(grounding) = &chunk.grounding_metadata {
+        merge_grounding(
+            target
+                .grounding_metadata
+                .get_or_insert_with(GroundingMetadata::default),
+            grounding,
+        );
+    }
+    if let Some(logprobs) = &chunk.logprobs_result {
+        let merged = target
+            .logprobs_result
+            .get_or_insert_with(LogprobsResult::default);
+        append(&mut merged.chosen_candidates, &logprobs.chosen_candidates);
+        append(&mut merged.top_candidates, &logprobs.top_candidates);
+    }
+    last(&mut target.finish_reason, &chunk.finish_reason);
+    last(&mut target.finish_message, &chunk.finish_message);
+    last(&mut target.token_count, &chunk.token_count);
+    last(&mut target.avg_logprobs, &chunk.avg_logprobs);
+    last(&mut target.safety_ratings, &chunk.safety_ratings);
+}
+
+fn merge_grounding(target: &mut GroundingMetadata, chunk: &GroundingMetadata) {
+    // Supports refer to grounding chunks by position, so shift them past the chunks that
+    // are already there.
+    let offset = target.grounding_chunks.as_ref().map_or(0, Vec::len) as i64;
+    let supports = chunk.grounding_supports.clone().map(|supports| {
+        supports
+            .into_iter()
+            .map(|mut s| {
+                for i in s.grounding_chunk_indices.iter_mut().flatten() {
+                    *i += offset;
+                }
+                s
+            })
+            .collect()
+    });
+    append(&mut target.grounding_chunks, &chunk.grounding_chunks);
+    append(&mut target.grounding_supports, &supports);
+    append(&mut target.retrieval_queries, &chunk.retrieval_queries);
+    append(&mut target.web_search_queries, &chunk.web_search_queries);
+    last(&mut target.retrieval_metadata, &chunk.retrieval_metadata);
+    last(&mut target.search_entry_point, &chunk.search_entry_point);
+}
+
+/// Replaces `target` with `value` if it is set.
+fn last<T: Clone>(target: &mut Option<T>, value: &Option<T>) {
+    if value.is_some() {
+        target.clone_from(value);
+    }
+}
+
+/// Appends the items of `value` to `target`.
+fn append<T: Clone>(target: &mut Option<Vec<T>>, value: &Option<Vec<T>>) {
+    if let Some(items) = value {
+        target
+            .get_or_insert_with(Vec::new)
+            .extend(items.iter().cloned());
+    }
+}
+
+/// Appends a streamed part, joining consecutive text chunks into a single part. Thoughts
+/// are only joined with thoughts, and a part carrying a thought signature ends its run.
+fn push_part(parts: &mut Vec<Part>, part: Part) {
+    if let (Some(last), Some(text)) = (parts.last_mut(), plain_text(&part)) {
+        if plain_text(last).is_some()
+            && last.is_thought() == part.is_thought()
+            && last.thought_signature.is_none()
+        {
+            last.text.get_or_insert_default().push_str(text);
+            last.thought_signature = part.thought_signature;
+            return;
+        }
+    }
+    parts.push(part);
+}
+
+/// The text of a part that holds nothing but text, which may be a thought.
+fn plain_text(part: &Part) -> Option<&str> {
+    let Part {
+        video_metadata: None,
+        code_execution_result: None,
+        executable_code: None,
+        file_data: None,
+        function_call: None,
+        function_response: None,
+        inline_data: None,
+        text: Some(text),
+        thought: _,
+        thought_signature: _,
+    } = part
+    else {
+        return None;
+    };
+    Some(text)
+}
+
+/// Adapts a response stream to yield only the text of the first candidate.
+///
+/// Chunks without text are skipped. The full response is still accumulated, and can be
+/// retrieved with [`TextDeltaStream::finish`] once the stream ends.
+pub struct TextDeltaStream {
+    inner: ResponseStream,
+    acc: StreamAccumulator,
+}
+
+impl TextDeltaStream {
+    pub fn new(inner: ResponseStream) -> Self {
+        Self {
+            inner,
+            acc: StreamAccumulator::new(),
+        }
+    }
+
+    /// The response merged so far.
+    pub fn response(&self) -> &GenerateContentResponse {
+        self.acc.response()
+    }
+
+    /// Returns the merged response.
+    pub fn finish(self) -> GenerateContentResponse {
+        self.acc.finish()
+    }
+}
+
+impl Stream for TextDeltaStream {
+    type Item = Result<String>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            let chunk = match ready!(this.inner.poll_next_unpin(cx)) {
+                Some(Ok(chunk)) => chunk,
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                None => return Poll::Ready(None),
+            };
+            this.acc.push(&chunk);
+            if let Some(text) = chunk.text().filter(|t| !t.is_empty()) {
+                return Poll::Ready(Some(Ok(text)));
+            }
+        }
+    }
+}
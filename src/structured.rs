@@ -0,0 +1,68 @@
+//! Structured output parsed into Rust types.
+
+use serde::de::DeserializeOwned;
+
+use crate::chat::{MODEL_ROLE, USER_ROLE};
+use crate::client::{blocked_error, Client};
+use crate::datatypes::{Content, GenerateContentReq, Part};
+use crate::error::*;
+use crate::schema::GenAiSchema;
+
+impl Client {
+    /// Generates a response constrained to the schema of `T` and parses it.
+    ///
+    /// Sets the request's response MIME type to JSON and its response schema to
+    /// `T::schema()`. Fails with [`GenAiError::Blocked`] if the prompt or response is
+    /// blocked or no candidate comes back, and with [`GenAiError::ParseOutput`] if the
+    /// output doesn't parse.
+    pub async fn generate_json<T>(&self, req: GenerateContentReq) -> Result<T>
+    where
+        T: GenAiSchema + DeserializeOwned,
+    {
+        self.generate_json_with_repair(req, 0).await
+    }
+
+    /// Like [`Client::generate_json`], but when the output doesn't parse, shows the model
+    /// its output and the parse error and asks it to try again, up to `max_repairs` times.
+    /// Blocked responses are not repaired.
+    pub async fn generate_json_with_repair<T>(
+        &self,
+        mut req: GenerateContentReq,
+        max_repairs: u32,
+    ) -> Result<T>
+    where
+        T: GenAiSchema + DeserializeOwned,
+    {
+        let config = req.generation_config.take().unwrap_or_default();
+        req.generation_config = Some(
+            config
+                .response_mime_type("application/json")
+                .response_schema(T::schema()),
+        );
+        let mut repairs = 0;
+        loop {
+            let response = self.generate_content(req.clone()).await?;
+            if let Some(err) = blocked_error(&response, true) {
+                return Err(err);
+            }
+            let text = response.text().unwrap_or_default();
+            let source = match serde_json::from_str(&text) {
+                Ok(value) => return Ok(value),
+                Err(source) => source,
+            };
+            if repairs >= max_repairs {
+                return Err(GenAiError::ParseOutput { text, source });
+            }
+            repairs += 1;
+            let prompt = format!(
+                "Your response could not be parsed: {}. Reply again with only JSON that \
+                 matches the response schema.",
+                source
+            );
+            req.contents
+                .push(Content::new(MODEL_ROLE, vec![Part::default().text(text)]));
+            req.contents
+                .push(Content::new(USER_ROLE, vec![Part::default().text(prompt)]));
+        }
+    }
+}
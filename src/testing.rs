@@ -0,0 +1,534 @@
+//! An in-process mock of the Gemini API, for testing code that uses this crate without
+//! network access. Requires the `testing` feature.
+//!
+//! A [`MockServer`] listens on a local port and answers each request with the next reply
+//! scripted for it, recording every request it receives.
+//!
+//! ```no_run
+//! # async fn run() -> google_genai::error::Result<()> {
+//! use google_genai::datatypes::{Content, GenerateContentReq};
+//! use google_genai::testing::{MockReply, MockServer};
+//!
+//! let server = MockServer::start().await;
+//! server.reply_to("generateContent", MockReply::text("Hello!"));
+//!
+//! let req = GenerateContentReq::default()
+//!     .model("gemini-2.0-flash")
+//!     .contents(vec![Content::user_text("Hi")]);
+//! let response = server.client().generate_content(req).await?;
+//! assert_eq!(response.text().as_deref(), Some("Hello!"));
+//!
+//! let request = server.last_request();
+//! assert_eq!(request.rpc(), Some("generateContent"));
+//! server.assert_all_replies_used();
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+use crate::client::{Client, ClientBuilder};
+use crate::datatypes::{
+    Candidate, Content, CountTokensResponse, FinishReason, GenerateContentReq,
+    GenerateContentResponse, Part,
+};
+
+/// The API key of clients created with [`MockServer::client`].
+pub const TEST_API_KEY: &str = "test-api-key";
+
+/// The model of requests created with [`request`].
+pub const TEST_MODEL: &str = "gemini-2.0-flash";
+
+/// A request for [`TEST_MODEL`] holding a single user message, "Hi".
+pub fn request() -> GenerateContentReq {
+    GenerateContentReq::default()
+        .model(TEST_MODEL)
+        .contents(vec![Content::user_text("Hi")])
+}
+
+/// A canned reply to a single request.
+#[derive(Debug, Clone)]
+pub struct MockReply {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: MockBody,
+    delay: Option<Duration>,
+    chunk_delay: Option<Duration>,
+}
+
+#[derive(Debug, Clone)]
+enum MockBody {
+    Json(String),
+    /// The data of each server-sent event.
+    Events(Vec<String>),
+    /// Close the connection without responding.
+    Disconnect,
+}
+
+impl MockReply {
+    /// A 200 response with `body` serialized as JSON.
+    pub fn json(body: impl Serialize) -> Self {
+        let body = serde_json::to_string(&body).expect("mock reply body must serialize");
+        Self::new(200, MockBody::Json(body))
+    }
+
+    /// A 200 response with a raw body, for example one that isn't valid JSON.
+    pub fn raw(body: impl Into<String>) -> Self {
+        Self::new(200, MockBody::Json(body.into()))
+    }
+
+    /// A `generateContent` response.
+    pub fn response(response: GenerateContentResponse) -> Self {
+        Self::json(response)
+    }
+
+    /// A `generateContent` response with a single candidate that says `text` and stops.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::response(text_chunk(text.into(), Some(FinishReason::Stop)))
+    }
+
+    /// A `countTokens` response.
+    pub fn count_tokens(total_tokens: i64) -> Self {
+        Self::json(CountTokensResponse {
+            total_tokens: Some(total_tokens),
+            ..Default::default()
+        })
+    }
+
+    /// An error response with a `google.rpc.Status` body.
+    pub fn error(status: u16, message: impl Into<String>) -> Self {
+        Self::json(status_body(status, message.into())).status(status)
+    }
+
+    /// A `streamGenerateContent` response that sends each chunk as a server-sent event.
+    pub fn stream(chunks: impl IntoIterator<Item = GenerateContentResponse>) -> Self {
+        let events = chunks
+            .into_iter()
+            .map(|c| serde_json::to_string(&c).expect("mock chunk must serialize"))
+            .collect();
+        Self::new(200, MockBody::Events(events))
+    }
+
+    /// A `streamGenerateContent` response that streams `texts` as separate chunks, the last
+    /// of which stops.
+    pub fn text_stream<I>(texts: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let mut chunks: Vec<_> = texts
+            .into_iter()
+            .map(|t| text_chunk(t.into(), None))
+            .collect();
+        if let Some(candidate) = chunks
+            .last_mut()
+            .and_then(|c| c.candidates.as_mut()?.first_mut())
+        {
+            candidate.finish_reason = Some(FinishReason::Stop);
+        }
+        Self::stream(chunks)
+    }
+
+    /// Closes the connection without sending a response.
+    pub fn disconnect() -> Self {
+        Self::new(200, MockBody::Disconnect)
+    }
+
+    /// Appends a raw server-sent event, such as an error object or `[DONE]`, to a stream.
+    ///
+    /// # Panics
+    ///
+    /// If the reply is not a stream.
+    pub fn event(mut self, data: impl Into<String>) -> Self {
+        match &mut self.body {
+            MockBody::Events(events) => events.push(data.into()),
+            _ => panic!("MockReply::event requires a stream reply"),
+        }
+        self
+    }
+
+    /// Appends an error event to a stream, as the server sends when generation fails midway.
+    pub fn stream_error(self, status: u16, message: impl Into<String>) -> Self {
+        self.event(status_body(status, message.into()).to_string())
+    }
+
+    /// Sets the HTTP status.
+    pub fn status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Adds a response header.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Waits before responding.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Waits before each event of a stream.
+    pub fn chunk_delay(mut self, delay: Duration) -> Self {
+        self.chunk_delay = Some(delay);
+        self
+    }
+
+    fn new(status: u16, body: MockBody) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body,
+            delay: None,
+            chunk_delay: None,
+        }
+    }
+}
+
+/// A chunk with a single candidate holding `text`.
+fn text_chunk(text: String, finish_reason: Option<FinishReason>) -> GenerateContentResponse {
+    GenerateContentResponse {
+        candidates: Some(vec![Candidate {
+            content: Some(Content::new("model", vec![Part::default().text(text)])),
+            finish_reason,
+            index: Some(0),
+            ..Default::default()
+        }]),
+        ..Default::default()
+    }
+}
+
+/// A `google.rpc.Status` error body.
+fn status_body(status: u16, message: String) -> serde_json::Value {
+    serde_json::json!({
+        "error": {
+            "code": status,
+            "message": message,
+            "status": rpc_status(status),
+        }
+    })
+}
+
+/// The canonical status name the API uses for an HTTP status.
+fn rpc_status(status: u16) -> &'static str {
+    match status {
+        400 => "INVALID_ARGUMENT",
+        401 => "UNAUTHENTICATED",
+        403 => "PERMISSION_DENIED",
+        404 => "NOT_FOUND",
+        409 => "ALREADY_EXISTS",
+        429 => "RESOURCE_EXHAUSTED",
+        499 => "CANCELLED",
+        500 => "INTERNAL",
+        501 => "NOT_IMPLEMENTED",
+        503 => "UNAVAILABLE",
+        504 => "DEADLINE_EXCEEDED",
+        _ => "UNKNOWN",
+    }
+}
+
+/// A request received by a [`MockServer`].
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    /// The HTTP method, e.g. `POST`.
+    pub method: String,
+    /// The path, including any query string.
+    pub path: String,
+    /// The request headers. Names are lowercase.
+    pub headers: HashMap<String, String>,
+    /// The raw body.
+    pub body: Vec<u8>,
+}
+
+impl RecordedRequest {
+    /// The RPC method the request invokes, e.g. `generateContent` for
+    /// `/v1beta/models/m:generateContent`.
+    pub fn rpc(&self) -> Option<&str> {
+        let path = self.path.split('?').next().unwrap_or_default();
+        let (_, rpc) = path.rsplit('/').next()?.split_once(':')?;
+        Some(rpc)
+    }
+
+    /// The value of a query parameter.
+    pub fn query(&self, name: &str) -> Option<&str> {
+        let (_, query) = self.path.split_once('?')?;
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find_map(|(k, v)| (k == name).then_some(v))
+    }
+
+    /// The value of a header.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    /// The body deserialized from JSON.
+    ///
+    /// # Panics
+    ///
+    /// If the body is not valid JSON for `T`.
+    pub fn json<T: DeserializeOwned>(&self) -> T {
+        serde_json::from_slice(&self.body).unwrap_or_else(|e| {
+            panic!(
+                "request body is not the expected JSON: {}\n{}",
+                e,
+                String::from_utf8_lossy(&self.body)
+            )
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Replies for any request.
+    replies: VecDeque<MockReply>,
+    /// Replies for a specific RPC method.
+    rpc_replies: HashMap<String, VecDeque<MockReply>>,
+    requests: Vec<RecordedRequest>,
+}
+
+impl State {
+    /// The next reply for `request`, preferring replies scripted for its RPC method.
+    fn next_reply(&mut self, request: &RecordedRequest) -> Option<MockReply> {
+        request
+            .rpc()
+            .and_then(|rpc| self.rpc_replies.get_mut(rpc)?.pop_front())
+            .or_else(|| self.replies.pop_front())
+    }
+}
+
+/// A local HTTP server that answers requests with scripted replies.
+///
+/// Replies scripted with [`MockServer::reply_to`] answer requests for that RPC method, in
+/// order; replies scripted with [`MockServer::reply`] answer any request that has no
+/// method-specific reply waiting. A request with no reply gets a 404 error. The server
+/// stops when it is dropped.
+///
+/// Request bodies must be sent with a `Content-Length`, which is how this crate's client
+/// sends them.
+#[derive(Debug)]
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    task: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Starts a server on a free local port.
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed to bind mock server");
+        let addr = listener.local_addr().expect("mock server has no address");
+        let state = Arc::new(Mutex::new(State::default()));
+        let task = tokio::spawn({
+            let state = state.clone();
+            async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(serve(stream, state.clone()));
+                }
+            }
+        });
+        Self { addr, state, task }
+    }
+
+    /// The base URL to point a client at.
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// An HTTP client that connects to the server directly. Proxy settings in the
+    /// environment would otherwise send requests for the local server elsewhere.
+    pub fn http_client(&self) -> reqwest::Client {
+        reqwest::Client::builder()
+            .no_proxy()
+            .build()
+            .expect("failed to build mock HTTP client")
+    }
+
+    /// A client builder pointed at the server, with a test API key and
+    /// [`MockServer::http_client`].
+    pub fn client_builder(&self) -> ClientBuilder {
+        Client::builder()
+            .base_url(self.base_url())
+            .api_key(TEST_API_KEY)
+            .http_client(self.http_client())
+    }
+
+    /// A client pointed at the server.
+    pub fn client(&self) -> Client {
+        self.client_builder()
+            .build()
+            .expect("failed to build mock client")
+    }
+
+    /// Scripts a reply for the next request that has no method-specific reply.
+    pub fn reply(&self, reply: MockReply) -> &Self {
+        self.state().replies.push_back(reply);
+        self
+    }
+
+    /// Scripts a reply for the next request for `rpc`, e.g. `streamGenerateContent`.
+    pub fn reply_to(&self, rpc: impl Into<String>, reply: MockReply) -> &Self {
+        self.state()
+            .rpc_replies
+            .entry(rpc.into())
+            .or_default()
+            .push_back(reply);
+        self
+    }
+
+    /// All requests received so far, in order.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state().requests.clone()
+    }
+
+    /// The requests received so far for `rpc`.
+    pub fn requests_to(&self, rpc: &str) -> Vec<RecordedRequest> {
+        self.state()
+            .requests
+            .iter()
+            .filter(|r| r.rpc() == Some(rpc))
+            .cloned()
+            .collect()
+    }
+
+    /// The most recent request.
+    ///
+    /// # Panics
+    ///
+    /// If no request has been received.
+    pub fn last_request(&self) -> RecordedRequest {
+        self.state()
+            .requests
+            .last()
+            .cloned()
+            .expect("mock server has received no requests")
+    }
+
+    /// Panics unless every scripted reply has been sent.
+    pub fn assert_all_replies_used(&self) {
+        let state = self.state();
+        let unused =
+            state.replies.len() + state.rpc_replies.values().map(VecDeque::len).sum::<usize>();
+        assert!(unused == 0, "{} scripted replies were not used", unused);
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        // A panic while the lock is held can only come from a failed assertion, which
+        // leaves the state consistent.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Handles a single connection: reads one request, records it and sends its reply.
+async fn serve(stream: TcpStream, state: Arc<Mutex<State>>) {
+    let mut stream = BufReader::new(stream);
+    let Some(request) = read_request(&mut stream).await else {
+        return;
+    };
+    let reply = {
+        let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+        let reply = state.next_reply(&request);
+        state.requests.push(request.clone());
+        reply
+    };
+    let reply = reply.unwrap_or_else(|| {
+        MockReply::error(
+            404,
+            format!("No reply scripted for {} {}", request.method, request.path),
+        )
+    });
+    // The client may hang up at any point, for example on a timeout, so write errors are
+    // expected and ignored.
+    let _ = write_reply(stream.get_mut(), reply).await;
+}
+
+async fn read_request(stream: &mut BufReader<TcpStream>) -> Option<RecordedRequest> {
+    let mut line = String::new();
+    stream.read_line(&mut line).await.ok()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let path = parts.next()?.to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        line.clear();
+        stream.read_line(&mut line).await.ok()?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line.split_once(':')?;
+        headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+    }
+
+    let len = headers
+        .get("content-length")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; len];
+    stream.read_exact(&mut body).await.ok()?;
+    Some(RecordedRequest {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+async fn write_reply(stream: &mut TcpStream, reply: MockReply) -> std::io::Result<()> {
+    if let Some(delay) = reply.delay {
+        tokio::time::sleep(delay).await;
+    }
+    let mut head = format!("HTTP/1.1 {} Mock\r\nConnection: close\r\n", reply.status);
+    for (name, value) in &reply.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    match reply.body {
+        MockBody::Disconnect => return Ok(()),
+        MockBody::Json(body) => {
+            head.push_str(&format!(
+                "Content-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+                body.len()
+            ));
+            stream.write_all(head.as_bytes()).await?;
+            stream.write_all(body.as_bytes()).await?;
+        }
+        // Without a length the body runs until the connection closes.
+        MockBody::Events(events) => {
+            head.push_str("Content-Type: text/event-stream\r\n\r\n");
+            stream.write_all(head.as_bytes()).await?;
+            for data in events {
+                if let Some(delay) = reply.chunk_delay {
+                    tokio::time::sleep(delay).await;
+                }
+                stream
+                    .write_all(format!("data: {}\r\n\r\n", data).as_bytes())
+                    .await?;
+                stream.flush().await?;
+            }
+        }
+    }
+    stream.shutdown().await
+}
@@ -0,0 +1,223 @@
+//! Automatic function calling.
+//!
+//! A [`ToolRegistry`] maps function names to async Rust handlers. Passing it to
+//! [`Client::generate_content_with_tools`] runs the function-calling loop: the model's
+//! calls are executed, their results are sent back, and this repeats until the model
+//! answers without calling any functions.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+
+use futures_util::future::{join_all, BoxFuture};
+
+use crate::chat::{MODEL_ROLE, USER_ROLE};
+use crate::client::Client;
+use crate::datatypes::{
+    Content, FunctionCall, FunctionDeclaration, FunctionResponse, GenerateContentReq,
+    GenerateContentResponse, Part, Tool,
+};
+use crate::error::*;
+
+/// The default limit on model round trips in a function-calling loop.
+pub const DEFAULT_MAX_ITERATIONS: usize = 10;
+
+type Handler =
+    Arc<dyn Fn(serde_json::Value) -> BoxFuture<'static, Result<serde_json::Value>> + Send + Sync>;
+
+/// Observes and gates function calls made by the model.
+///
+/// Every method has a default, so implementations only override what they need.
+pub trait ToolHooks: Send + Sync {
+    /// Decides whether a call may run. A rejected call is reported back to the model as
+    /// an error.
+    fn approve<'a>(&'a self, _call: &'a FunctionCall) -> BoxFuture<'a, bool> {
+        Box::pin(async { true })
+    }
+
+    /// Called with the response to every call, including failed and rejected ones.
+    fn on_response(&self, _call: &FunctionCall, _response: &FunctionResponse) {}
+}
+
+struct RegisteredTool {
+    declaration: FunctionDeclaration,
+    handler: Handler,
+}
+
+/// A set of functions the model may call, with their handlers.
+#[derive(Clone)]
+pub struct ToolRegistry {
+    tools: BTreeMap<String, Arc<RegisteredTool>>,
+    max_iterations: usize,
+    hooks: Option<Arc<dyn ToolHooks>>,
+}
+
+impl Default for ToolRegistry {
+    fn default() -> Self {
+        Self {
+            tools: BTreeMap::new(),
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            hooks: None,
+        }
+    }
+}
+
+impl fmt::Debug for ToolRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ToolRegistry")
+            .field("tools", &self.tools.keys().collect::<Vec<_>>())
+            .field("max_iterations", &self.max_iterations)
+            .field("hooks", &self.hooks.is_some())
+            .finish()
+    }
+}
+
+impl ToolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a handler for the function described by `declaration`, replacing any
+    /// handler registered under the same name.
+    ///
+    /// The handler receives the call's arguments. A JSON object result is sent to the model
+    /// as is; other values are wrapped as `{"result": value}`. Errors are reported to the
+    /// model as `{"error": message}` rather than aborting the loop.
+    pub fn register<F, Fut>(mut self, declaration: FunctionDeclaration, handler: F) -> Self
+    where
+        F: Fn(serde_json::Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<serde_json::Value>> + Send + 'static,
+    {
+        let handler: Handler = Arc::new(move |args| Box::pin(handler(args)));
+        self.tools.insert(
+            declaration.name.clone(),
+            Arc::new(RegisteredTool {
+                declaration,
+                handler,
+            }),
+        );
+        self
+    }
+
+    /// Sets the maximum number of model round trips before the loop gives up.
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Sets hooks that approve and observe calls.
+    pub fn hooks(mut self, hooks: impl ToolHooks + 'static) -> Self {
+        self.hooks = Some(Arc::new(hooks));
+        self
+    }
+
+    /// The declarations of all registered functions.
+    pub fn declarations(&self) -> Vec<FunctionDeclaration> {
+        self.tools.values().map(|t| t.declaration.clone()).collect()
+    }
+
+    /// A tool declaring all registered functions.
+    pub fn tool(&self) -> Tool {
+        Tool {
+            function_declarations: Some(self.declarations()),
+            retrieval: None,
+            google_search: None,
+            google_search_retrieval: None,
+            code_execution: None,
+        }
+    }
+
+    /// Runs a single call and builds the response to send back to the model.
+    pub async fn call(&self, call: &FunctionCall) -> FunctionResponse {
+        let result = match self.tools.get(&call.name) {
+            None => Err(format!("Unknown function: {}", call.name)),
+            Some(tool) => {
+                let approved = match &self.hooks {
+                    Some(hooks) => hooks.approve(call).await,
+                    None => true,
+                };
+                if approved {
+                    let args = call.args.clone().unwrap_or_else(|| serde_json::json!({}));
+                    (tool.handler)(args).await.map_err(error_message)
+                } else {
+                    Err("Call was not approved".to_string())
+                }
+            }
+        };
+        let response = match result {
+            Ok(value @ serde_json::Value::Object(_)) => value,
+            Ok(value) => serde_json::json!({ "result": value }),
+            Err(message) => serde_json::json!({ "error": message }),
+        };
+        let response = FunctionResponse {
+            id: call.id.clone(),
+            name: call.name.clone(),
+            response,
+        };
+        if let Some(hooks) = &self.hooks {
+            hooks.on_response(call, &response);
+        }
+        response
+    }
+}
+
+/// The message reported to the model for a failed call. Errors raised by handlers, and
+/// invalid arguments, are sent in the handler's own words, without the prefix that the
+/// error's `Display` adds.
+fn error_message(e: GenAiError) -> String {
+    match e {
+        GenAiError::Internal(message) | GenAiError::InvalidRequest(message) => message,
+        e => e.to_string(),
+    }
+}
+
+/// The outcome of a function-calling loop.
+#[derive(Debug, Clone)]
+pub struct ToolLoopResult {
+    /// The final response, which contains no function calls.
+    pub response: GenerateContentResponse,
+    /// The conversation including every function call and response, but not the final
+    /// reply.
+    pub contents: Vec<Content>,
+}
+
+impl Client {
+    /// Generates content, executing the functions the model calls until it replies
+    /// without calling any.
+    ///
+    /// The registry's functions are added to the request's tools. When the model emits
+    /// several calls in one turn they run concurrently. Fails if the model is still
+    /// calling functions after the registry's `max_iterations` round trips.
+    pub async fn generate_content_with_tools(
+        &self,
+        mut req: GenerateContentReq,
+        registry: &ToolRegistry,
+    ) -> Result<ToolLoopResult> {
+        req.tools.get_or_insert_with(Vec::new).push(registry.tool());
+        for _ in 0..registry.max_iterations {
+            let response = self.generate_content(req.clone()).await?;
+            let content = response.candidate().and_then(|c| c.content.clone());
+            let calls: Vec<FunctionCall> = response.function_calls().into_iter().cloned().collect();
+            let Some(mut content) = content.filter(|_| !calls.is_empty()) else {
+                return Ok(ToolLoopResult {
+                    response,
+                    contents: req.contents,
+                });
+            };
+
+            content.role = Some(MODEL_ROLE.to_string());
+            req.contents.push(content);
+            let responses = join_all(calls.iter().map(|c| registry.call(c))).await;
+            let parts = responses
+                .into_iter()
+                .map(|r| Part::default().function_response(r))
+                .collect();
+            req.contents.push(Content::new(USER_ROLE, parts));
+        }
+        Err(GenAiError::Internal(format!(
+            "Model was still calling functions after {} iterations",
+            registry.max_iterations
+        )))
+    }
+}
@@ -0,0 +1,92 @@
+//! Application default credentials read the process environment, so these checks live in
+//! their own test binary and run as a single test.
+
+use std::path::Path;
+
+use google_genai::auth::application_default_credentials;
+use google_genai::error::ErrorKind;
+use google_genai::testing::{MockReply, MockServer};
+
+const ENV_VARS: [&str; 5] = [
+    "GOOGLE_APPLICATION_CREDENTIALS",
+    "CLOUDSDK_CONFIG",
+    "GCE_METADATA_HOST",
+    "K_SERVICE",
+    "APPDATA",
+];
+
+fn running_on_compute_engine() -> bool {
+    std::fs::read_to_string("/sys/class/dmi/id/product_name")
+        .is_ok_and(|name| name.trim_start().starts_with("Google"))
+}
+
+fn write_authorized_user(path: &Path, token_uri: &str) {
+    let json = serde_json::json!({
+        "type": "authorized_user",
+        "client_id": "id",
+        "client_secret": "secret",
+        "refresh_token": "refresh",
+        "token_uri": token_uri,
+    });
+    std::fs::write(path, json.to_string()).unwrap();
+}
+
+#[tokio::test]
+async fn application_default_credentials_lookup_order() {
+    for var in ENV_VARS {
+        std::env::remove_var(var);
+    }
+    let dir = std::env::temp_dir().join(format!("google-genai-adc-{}", std::process::id()));
+    let gcloud = dir.join("gcloud");
+    std::fs::create_dir_all(&gcloud).unwrap();
+    let server = MockServer::start().await;
+    let token_uri = format!("{}/token", server.base_url());
+
+    // Nothing configured and not on Google Cloud: no silent metadata server fallback.
+    std::env::set_var("CLOUDSDK_CONFIG", &gcloud);
+    if !running_on_compute_engine() {
+        let err = application_default_credentials().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Auth);
+        assert!(err.to_string().contains("No credentials found"), "{}", err);
+    }
+
+    // An explicit metadata host counts as running on Google Cloud.
+    std::env::set_var(
+        "GCE_METADATA_HOST",
+        server.base_url().trim_start_matches("http://"),
+    );
+    server.reply(MockReply::json(
+        serde_json::json!({"access_token": "from-metadata", "expires_in": 3600}),
+    ));
+    let auth = application_default_credentials().unwrap();
+    assert_eq!(auth.credential().await.unwrap().secret(), "from-metadata");
+    assert!(server.last_request().path.starts_with("/computeMetadata/"));
+
+    // The gcloud well-known file takes precedence over the metadata server.
+    write_authorized_user(
+        &gcloud.join("application_default_credentials.json"),
+        &token_uri,
+    );
+    server.reply(MockReply::json(
+        serde_json::json!({"access_token": "from-gcloud", "expires_in": 3600}),
+    ));
+    let auth = application_default_credentials().unwrap();
+    assert_eq!(auth.credential().await.unwrap().secret(), "from-gcloud");
+
+    // GOOGLE_APPLICATION_CREDENTIALS takes precedence over both.
+    let explicit = dir.join("explicit.json");
+    write_authorized_user(&explicit, &token_uri);
+    std::env::set_var("GOOGLE_APPLICATION_CREDENTIALS", &explicit);
+    server.reply(MockReply::json(
+        serde_json::json!({"access_token": "from-env", "expires_in": 3600}),
+    ));
+    let auth = application_default_credentials().unwrap();
+    assert_eq!(auth.credential().await.unwrap().secret(), "from-env");
+
+    // A path that does not exist is an error, not a reason to keep looking.
+    std::env::set_var("GOOGLE_APPLICATION_CREDENTIALS", dir.join("missing.json"));
+    let err = application_default_credentials().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Auth);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
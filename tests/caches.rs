@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use futures_util::TryStreamExt;
+use google_genai::datatypes::{CachedContent, Content};
+use google_genai::error::ErrorKind;
+use google_genai::testing::{MockReply, MockServer};
+use serde_json::{json, Value};
+
+fn cache_reply(name: &str) -> MockReply {
+    MockReply::json(json!({
+        "name": name,
+        "model": "models/gemini-2.0-flash",
+        "ttl": "300s",
+        "usageMetadata": { "totalTokenCount": 4096 },
+    }))
+}
+
+#[test]
+fn ttls_are_encoded_as_protobuf_durations() {
+    let ttl = |d: Duration| CachedContent::default().ttl_duration(d).ttl.unwrap();
+    assert_eq!(ttl(Duration::from_secs(300)), "300s");
+    assert_eq!(ttl(Duration::from_millis(1500)), "1.5s");
+    assert_eq!(ttl(Duration::ZERO), "0s");
+}
+
+#[tokio::test]
+async fn create_qualifies_the_model_and_sends_the_ttl() {
+    let server = MockServer::start().await;
+    server.reply(cache_reply("cachedContents/abc-123"));
+
+    let cache = server
+        .client()
+        .create_cached_content(
+            CachedContent::default()
+                .model("gemini-2.0-flash")
+                .contents(vec![Content::user_text("A long document")])
+                .ttl_duration(Duration::from_secs(300)),
+        )
+        .await
+        .unwrap();
+    assert_eq!(cache.name.as_deref(), Some("cachedContents/abc-123"));
+    assert_eq!(
+        cache.request().cached_content.as_deref(),
+        Some("cachedContents/abc-123")
+    );
+
+    let sent = server.last_request();
+    assert_eq!(sent.method, "POST");
+    assert_eq!(sent.path, "/v1beta/cachedContents");
+    let body: Value = sent.json();
+    assert_eq!(body["model"], "models/gemini-2.0-flash");
+    assert_eq!(body["ttl"], "300s");
+    assert_eq!(body["contents"][0]["parts"][0]["text"], "A long document");
+}
+
+#[tokio::test]
+async fn create_requires_a_model() {
+    let server = MockServer::start().await;
+    let err = server
+        .client()
+        .create_cached_content(CachedContent::default())
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidRequest);
+    assert!(server.requests().is_empty());
+}
+
+#[tokio::test]
+async fn get_accepts_ids_and_resource_names() {
+    let server = MockServer::start().await;
+    server
+        .reply(cache_reply("cachedContents/abc-123"))
+        .reply(cache_reply("cachedContents/abc-123"));
+    let client = server.client();
+
+    client.get_cached_content("abc-123").await.unwrap();
+    client
+        .get_cached_content("cachedContents/abc-123")
+        .await
+        .unwrap();
+
+    for request in server.requests() {
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/v1beta/cachedContents/abc-123");
+    }
+}
+
+#[tokio::test]
+async fn list_follows_page_tokens() {
+    let server = MockServer::start().await;
+    server
+        .reply(MockReply::json(json!({
+            "cachedContents": [{ "name": "cachedContents/a" }],
+            "nextPageToken": "page-2",
+        })))
+        .reply(MockReply::json(json!({
+            "cachedContents": [{ "name": "cachedContents/b" }],
+        })));
+
+    let caches: Vec<_> = server
+        .client()
+        .list_cached_contents(Some(1))
+        .try_collect()
+        .await
+        .unwrap();
+    let names: Vec<_> = caches.iter().filter_map(|c| c.name.as_deref()).collect();
+    assert_eq!(names, ["cachedContents/a", "cachedContents/b"]);
+
+    let sent = server.requests();
+    assert_eq!(sent.len(), 2);
+    assert_eq!(sent[0].query("pageSize"), Some("1"));
+    assert_eq!(sent[0].query("pageToken"), None);
+    assert_eq!(sent[1].query("pageToken"), Some("page-2"));
+}
+
+#[tokio::test]
+async fn update_sends_only_the_ttl() {
+    let server = MockServer::start().await;
+    server.reply(cache_reply("cachedContents/abc-123"));
+
+    server
+        .client()
+        .update_cached_content_ttl("cachedContents/abc-123", Duration::from_millis(1500))
+        .await
+        .unwrap();
+
+    let sent = server.last_request();
+    assert_eq!(sent.method, "PATCH");
+    assert!(
+        sent.path.starts_with("/v1beta/cachedContents/abc-123?"),
+        "{}",
+        sent.path
+    );
+    assert_eq!(sent.query("updateMask"), Some("ttl"));
+    let body: Value = sent.json();
+    assert_eq!(body, json!({ "ttl": "1.5s" }));
+}
+
+#[tokio::test]
+async fn delete_sends_a_delete_request() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::json(json!({})));
+
+    server
+        .client()
+        .delete_cached_content("abc-123")
+        .await
+        .unwrap();
+
+    let sent = server.last_request();
+    assert_eq!(sent.method, "DELETE");
+    assert_eq!(sent.path, "/v1beta/cachedContents/abc-123");
+}
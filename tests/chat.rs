@@ -0,0 +1,237 @@
+use futures_util::StreamExt;
+use google_genai::datatypes::{Content, Part};
+use google_genai::error::{ErrorKind, GenAiError};
+use google_genai::testing::{request, MockReply, MockServer};
+use serde_json::{json, Value};
+
+fn model_text(text: &str) -> Content {
+    Content::new("model", vec![Part::default().text(text)])
+}
+
+/// The roles and texts of a history, for comparison.
+fn turns(history: &[Content]) -> Vec<(String, String)> {
+    history
+        .iter()
+        .map(|c| {
+            let text = c
+                .parts
+                .iter()
+                .flatten()
+                .filter_map(|p| p.text.clone())
+                .collect();
+            (c.role.clone().unwrap_or_default(), text)
+        })
+        .collect()
+}
+
+fn pairs(turns: &[(&str, &str)]) -> Vec<(String, String)> {
+    turns
+        .iter()
+        .map(|(role, text)| (role.to_string(), text.to_string()))
+        .collect()
+}
+
+#[tokio::test]
+async fn send_records_both_turns_and_sends_the_history() {
+    let server = MockServer::start().await;
+    server
+        .reply_to("generateContent", MockReply::text("Hello!"))
+        .reply_to("generateContent", MockReply::text("Fine, thanks."));
+    let mut chat = server
+        .client()
+        .start_chat(request().contents(vec![]))
+        .unwrap();
+
+    chat.send(Content::user_text("Hi")).await.unwrap();
+    chat.send(Content::user_text("How are you?")).await.unwrap();
+
+    assert_eq!(
+        turns(chat.history()),
+        pairs(&[
+            ("user", "Hi"),
+            ("model", "Hello!"),
+            ("user", "How are you?"),
+            ("model", "Fine, thanks."),
+        ])
+    );
+    let body: Value = server.last_request().json();
+    assert_eq!(body["contents"].as_array().unwrap().len(), 3);
+    assert_eq!(body["contents"][1]["role"], "model");
+    assert_eq!(body["contents"][2]["parts"][0]["text"], "How are you?");
+}
+
+#[tokio::test]
+async fn replies_without_content_are_not_recorded() {
+    let server = MockServer::start().await;
+    server
+        .reply(MockReply::json(json!({
+            "candidates": [{ "index": 0, "finishReason": "STOP" }],
+        })))
+        .reply(MockReply::json(json!({
+            "candidates": [{
+                "content": { "role": "model", "parts": [] },
+                "index": 0,
+                "finishReason": "STOP",
+            }],
+        })));
+    let mut chat = server
+        .client()
+        .start_chat(request().contents(vec![]))
+        .unwrap();
+
+    chat.send(Content::user_text("Hi")).await.unwrap();
+    chat.send(Content::user_text("Hi")).await.unwrap();
+    assert!(chat.history().is_empty());
+}
+
+#[tokio::test]
+async fn blocked_messages_leave_the_history_unchanged() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::json(json!({
+        "promptFeedback": { "blockReason": "SAFETY" },
+    })));
+    let mut chat = server
+        .client()
+        .start_chat(request().contents(vec![]))
+        .unwrap();
+
+    let err = chat.send(Content::user_text("Hi")).await.unwrap_err();
+    assert!(matches!(err, GenAiError::Blocked { .. }), "{:?}", err);
+    assert!(chat.history().is_empty());
+}
+
+#[tokio::test]
+async fn histories_must_alternate_between_user_and_model() {
+    let server = MockServer::start().await;
+    let client = server.client();
+    let invalid = [
+        vec![Content::user_text("Hi"), Content::user_text("Hi")],
+        vec![model_text("Hello!")],
+        vec![Content::user_text("Hi")],
+        vec![
+            Content::user_text("Hi"),
+            model_text("Hello!"),
+            model_text("Hi"),
+        ],
+    ];
+    for history in invalid {
+        let err = client.start_chat(request().contents(history)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidRequest, "{:?}", err);
+    }
+
+    let mut chat = client
+        .start_chat(request().contents(vec![Content::user_text("Hi"), model_text("Hello!")]))
+        .unwrap();
+    let err = chat.send(model_text("Hi")).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidRequest);
+    assert_eq!(chat.history().len(), 2);
+    assert!(server.requests().is_empty());
+}
+
+#[tokio::test]
+async fn restore_replaces_the_history() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::text("Hello!"));
+    let mut chat = server
+        .client()
+        .start_chat(request().contents(vec![]))
+        .unwrap();
+    let snapshot = chat.snapshot();
+
+    chat.send(Content::user_text("Hi")).await.unwrap();
+    assert_eq!(chat.history().len(), 2);
+    let saved = chat.snapshot();
+
+    chat.restore(snapshot).unwrap();
+    assert!(chat.history().is_empty());
+    chat.restore(saved).unwrap();
+    assert_eq!(
+        turns(chat.history()),
+        pairs(&[("user", "Hi"), ("model", "Hello!")])
+    );
+
+    let err = chat.restore(vec![Content::user_text("Hi")]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidRequest);
+    assert_eq!(chat.history().len(), 2);
+
+    chat.clear();
+    assert!(chat.history().is_empty());
+}
+
+#[tokio::test]
+async fn streamed_replies_are_recorded_once() {
+    let server = MockServer::start().await;
+    server.reply_to(
+        "streamGenerateContent",
+        MockReply::text_stream(["Hel", "lo", "!"]),
+    );
+    let mut chat = server
+        .client()
+        .start_chat(request().contents(vec![]))
+        .unwrap();
+
+    let mut stream = chat.send_stream(Content::user_text("Hi")).await.unwrap();
+    let mut chunks = 0;
+    while let Some(chunk) = stream.next().await {
+        chunk.unwrap();
+        chunks += 1;
+    }
+    assert!(stream.next().await.is_none());
+    drop(stream);
+
+    assert_eq!(chunks, 3);
+    assert_eq!(
+        turns(chat.history()),
+        pairs(&[("user", "Hi"), ("model", "Hello!")])
+    );
+    assert_eq!(chat.history()[1].parts.as_ref().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn failed_streams_leave_the_history_unchanged() {
+    let server = MockServer::start().await;
+    server.reply_to(
+        "streamGenerateContent",
+        MockReply::stream([serde_json::from_value(json!({
+            "candidates": [{
+                "content": { "role": "model", "parts": [{ "text": "Hel" }] },
+                "index": 0,
+            }],
+        }))
+        .unwrap()])
+        .stream_error(500, "Internal error"),
+    );
+    let mut chat = server
+        .client()
+        .start_chat(request().contents(vec![]))
+        .unwrap();
+
+    let mut stream = chat.send_stream(Content::user_text("Hi")).await.unwrap();
+    let mut failed = false;
+    while let Some(chunk) = stream.next().await {
+        failed |= chunk.is_err();
+    }
+    drop(stream);
+
+    assert!(failed);
+    assert!(chat.history().is_empty());
+}
+
+#[tokio::test]
+async fn dropped_streams_leave_the_history_unchanged() {
+    let server = MockServer::start().await;
+    server.reply_to(
+        "streamGenerateContent",
+        MockReply::text_stream(["Hel", "lo", "!"]),
+    );
+    let mut chat = server
+        .client()
+        .start_chat(request().contents(vec![]))
+        .unwrap();
+
+    let mut stream = chat.send_stream(Content::user_text("Hi")).await.unwrap();
+    stream.next().await.unwrap().unwrap();
+    drop(stream);
+
+    assert!(chat.history().is_empty());
+}
@@ -0,0 +1,280 @@
+use std::time::{Duration, Instant};
+
+use futures_util::StreamExt;
+use google_genai::datatypes::{Candidate, Content, FinishReason, GenerateContentResponse, Part};
+use google_genai::error::{ErrorKind, GenAiError};
+use google_genai::testing::{request, MockReply, MockServer, TEST_API_KEY};
+use google_genai::{CancellationToken, RequestOptions, StreamAccumulator, TextDeltaStream};
+
+#[tokio::test]
+async fn client_timeout_does_not_cut_off_streams() {
+    let server = MockServer::start().await;
+    server.reply_to(
+        "streamGenerateContent",
+        MockReply::text_stream(["a", "b", "c", "d"]).chunk_delay(Duration::from_millis(150)),
+    );
+    let client = server
+        .client_builder()
+        .timeout(Duration::from_millis(300))
+        .build()
+        .unwrap();
+
+    let stream = client.generate_content_stream(request()).await.unwrap();
+    let mut stream = TextDeltaStream::new(stream);
+    let mut text = String::new();
+    while let Some(delta) = stream.next().await {
+        text.push_str(&delta.unwrap());
+    }
+    assert_eq!(text, "abcd");
+}
+
+#[tokio::test]
+async fn client_timeout_applies_to_generate_content() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::text("late").delay(Duration::from_millis(500)));
+    let client = server
+        .client_builder()
+        .timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+
+    let err = client.generate_content(request()).await.unwrap_err();
+    assert!(matches!(err, GenAiError::Timeout), "{:?}", err);
+}
+
+#[tokio::test]
+async fn generate_content_sends_the_request() {
+    let server = MockServer::start().await;
+    server.reply_to("generateContent", MockReply::text("Hello!"));
+
+    let response = server.client().generate_content(request()).await.unwrap();
+    assert_eq!(response.text().as_deref(), Some("Hello!"));
+    assert_eq!(response.finish_reason(), Some(&FinishReason::Stop));
+
+    let sent = server.last_request();
+    assert_eq!(sent.method, "POST");
+    assert_eq!(sent.path, "/v1beta/models/gemini-2.0-flash:generateContent");
+    assert_eq!(sent.rpc(), Some("generateContent"));
+    assert_eq!(sent.header("x-goog-api-key"), Some(TEST_API_KEY));
+    let body: serde_json::Value = sent.json();
+    assert_eq!(body["contents"][0]["role"], "user");
+    assert_eq!(body["contents"][0]["parts"][0]["text"], "Hi");
+    server.assert_all_replies_used();
+}
+
+#[tokio::test]
+async fn stream_yields_each_chunk() {
+    let server = MockServer::start().await;
+    server.reply_to(
+        "streamGenerateContent",
+        MockReply::text_stream(["Hel", "lo", "!"]),
+    );
+
+    let stream = server
+        .client()
+        .generate_content_stream(request())
+        .await
+        .unwrap();
+    let response = StreamAccumulator::collect(stream).await.unwrap();
+    assert_eq!(response.text().as_deref(), Some("Hello!"));
+    assert_eq!(response.finish_reason(), Some(&FinishReason::Stop));
+    assert_eq!(server.last_request().query("alt"), Some("sse"));
+}
+
+#[tokio::test]
+async fn count_tokens_wraps_the_request() {
+    let server = MockServer::start().await;
+    server.reply_to("countTokens", MockReply::count_tokens(7));
+
+    let response = server.client().count_tokens(&request()).await.unwrap();
+    assert_eq!(response.total_tokens, Some(7));
+
+    let body: serde_json::Value = server.last_request().json();
+    let wrapped = &body["generateContentRequest"];
+    assert_eq!(wrapped["model"], "models/gemini-2.0-flash");
+    assert_eq!(wrapped["contents"][0]["parts"][0]["text"], "Hi");
+}
+
+#[tokio::test]
+async fn error_replies_become_remote_errors() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::error(404, "models/nope is not found"));
+
+    let err = server
+        .client()
+        .generate_content(request())
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Remote);
+    assert_eq!(err.rpc_status(), Some("NOT_FOUND"));
+    match err {
+        GenAiError::Remote {
+            status, message, ..
+        } => {
+            assert_eq!(status, 404);
+            assert_eq!(message, "models/nope is not found");
+        }
+        other => panic!("expected a remote error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn unscripted_requests_get_not_found() {
+    let server = MockServer::start().await;
+    server.reply_to("countTokens", MockReply::count_tokens(1));
+
+    let err = server
+        .client()
+        .generate_content(request())
+        .await
+        .unwrap_err();
+    assert_eq!(err.rpc_status(), Some("NOT_FOUND"));
+    assert!(err.to_string().contains("No reply scripted"), "{}", err);
+}
+
+#[tokio::test]
+#[should_panic(expected = "1 scripted replies were not used")]
+async fn unused_replies_fail_the_assertion() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::text("never sent"));
+    server.assert_all_replies_used();
+}
+
+#[tokio::test]
+async fn delayed_replies_respect_request_timeouts() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::text("slow").delay(Duration::from_millis(100)));
+    server.reply(MockReply::text("too slow").delay(Duration::from_millis(500)));
+    let client = server.client();
+    let options = RequestOptions::default().timeout(Duration::from_millis(300));
+
+    let response = client
+        .generate_content_with_options(request(), options.clone())
+        .await
+        .unwrap();
+    assert_eq!(response.text().as_deref(), Some("slow"));
+    let err = client
+        .generate_content_with_options(request(), options)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, GenAiError::Timeout), "{:?}", err);
+}
+
+#[tokio::test]
+async fn slow_stream_chunks_hit_the_idle_timeout() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::text_stream(["a", "b"]).chunk_delay(Duration::from_millis(300)));
+    let options = RequestOptions::default().idle_timeout(Duration::from_millis(100));
+
+    let mut stream = server
+        .client()
+        .generate_content_stream_with_options(request(), options)
+        .await
+        .unwrap();
+    let err = stream.next().await.unwrap().unwrap_err();
+    assert!(matches!(err, GenAiError::Timeout), "{:?}", err);
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn cancellation_interrupts_requests() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::text("late").delay(Duration::from_secs(5)));
+    let token = CancellationToken::new();
+    let options = RequestOptions::default().cancellation(token.clone());
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        token.cancel();
+    });
+
+    let start = Instant::now();
+    let err = server
+        .client()
+        .generate_content_with_options(request(), options)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, GenAiError::Cancelled), "{:?}", err);
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+#[tokio::test]
+async fn cancellation_interrupts_streams() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::text_stream(["a", "b"]).chunk_delay(Duration::from_millis(500)));
+    let token = CancellationToken::new();
+    let options = RequestOptions::default().cancellation(token.clone());
+
+    let mut stream = server
+        .client()
+        .generate_content_stream_with_options(request(), options)
+        .await
+        .unwrap();
+    let first = stream.next().await.unwrap().unwrap();
+    assert_eq!(first.text().as_deref(), Some("a"));
+
+    let start = Instant::now();
+    token.cancel();
+    let err = stream.next().await.unwrap().unwrap_err();
+    assert!(matches!(err, GenAiError::Cancelled), "{:?}", err);
+    assert!(start.elapsed() < Duration::from_millis(250));
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn disconnects_are_transport_errors() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::disconnect());
+
+    let err = server
+        .client()
+        .generate_content(request())
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Transport);
+    // The cause is left to the source chain rather than repeated in the message.
+    assert_eq!(err.to_string(), "Transport error");
+    assert!(std::error::Error::source(&err).is_some());
+}
+
+#[tokio::test]
+async fn invalid_json_is_a_decode_error() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::raw("not json"));
+
+    let err = server
+        .client()
+        .generate_content(request())
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Decode);
+    assert_eq!(err.to_string(), "Failed to decode response");
+    match &err {
+        GenAiError::Decode { body, .. } => assert_eq!(body, "not json"),
+        other => panic!("expected Decode, got {:?}", other),
+    }
+    assert!(std::error::Error::source(&err).is_some());
+}
+
+#[tokio::test]
+async fn streams_cut_off_before_finishing_are_truncated() {
+    let partial = |text: &str| {
+        GenerateContentResponse::default().candidates(vec![Candidate::default()
+            .content(Content::new("model", vec![Part::default().text(text)]))
+            .index(0)])
+    };
+    let server = MockServer::start().await;
+    // No chunk carries a finish reason before the connection closes.
+    server.reply(MockReply::stream([partial("a"), partial("b")]));
+
+    let stream = server
+        .client()
+        .generate_content_stream(request())
+        .await
+        .unwrap();
+    let items: Vec<_> = stream.collect().await;
+    assert_eq!(items.len(), 3);
+    assert!(items[0].is_ok() && items[1].is_ok());
+    let err = items[2].as_ref().unwrap_err();
+    assert!(matches!(err, GenAiError::StreamTruncated), "{:?}", err);
+    assert!(err.is_retryable());
+}
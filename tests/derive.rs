@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+
+use google_genai::datatypes::{Schema, Type};
+use google_genai::{tool, GenAiSchema};
+use serde::{Deserialize, Serialize};
+
+/// The property names of an object schema, in order.
+fn property_names(schema: &Schema) -> Vec<String> {
+    schema.property_ordering.clone().unwrap()
+}
+
+/// The keys serde writes for a value, in order.
+fn serde_keys<T: Serialize>(value: &T) -> Vec<String> {
+    let json = serde_json::to_string(value).unwrap();
+    let map: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&json).unwrap();
+    map.keys().cloned().collect()
+}
+
+fn property<'a>(schema: &'a Schema, name: &str) -> &'a Schema {
+    &schema.properties.as_ref().unwrap()[name]
+}
+
+macro_rules! rename_all_matches_serde {
+    ($($test:ident: $rule:literal,)*) => {$(
+        #[test]
+        fn $test() {
+            #[derive(Serialize, Deserialize, GenAiSchema, Default)]
+            #[serde(rename_all = $rule)]
+            #[allow(dead_code)]
+            struct Fields {
+                user_id: u32,
+                display_name_2: String,
+                r#type: String,
+            }
+
+            #[derive(Serialize, Deserialize, GenAiSchema)]
+            #[serde(rename_all = $rule)]
+            #[allow(dead_code)]
+            enum Variants {
+                HttpRequest,
+                Done,
+            }
+
+            // Keys come out of `serde_json::Map` sorted, so compare sorted names.
+            let mut names = property_names(&Fields::schema());
+            names.sort();
+            assert_eq!(names, serde_keys(&Fields::default()));
+
+            let values = Variants::schema().r#enum.unwrap();
+            let expected: Vec<String> = [Variants::HttpRequest, Variants::Done]
+                .iter()
+                .map(|v| serde_json::to_value(v).unwrap().as_str().unwrap().to_string())
+                .collect();
+            assert_eq!(values, expected);
+        }
+    )*};
+}
+
+rename_all_matches_serde! {
+    rename_all_lowercase: "lowercase",
+    rename_all_uppercase: "UPPERCASE",
+    rename_all_pascal_case: "PascalCase",
+    rename_all_camel_case: "camelCase",
+    rename_all_snake_case: "snake_case",
+    rename_all_screaming_snake_case: "SCREAMING_SNAKE_CASE",
+    rename_all_kebab_case: "kebab-case",
+    rename_all_screaming_kebab_case: "SCREAMING-KEBAB-CASE",
+}
+
+/// A person.
+#[derive(Serialize, Deserialize, GenAiSchema)]
+#[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
+struct Person {
+    /// Their full name.
+    full_name: String,
+    #[serde(rename = "years")]
+    age: u8,
+    #[serde(rename(serialize = "mail_out", deserialize = "mail"))]
+    email: Option<String>,
+    #[serde(skip)]
+    cache: Vec<u8>,
+    #[serde(default)]
+    tags: Vec<String>,
+    nickname: Option<String>,
+}
+
+#[test]
+fn struct_fields() {
+    let schema = Person::schema();
+    assert_eq!(schema.r#type, Some(Type::Object));
+    assert_eq!(schema.description.as_deref(), Some("A person."));
+    assert_eq!(
+        property_names(&schema),
+        ["fullName", "years", "mail", "tags", "nickname"]
+    );
+    assert_eq!(
+        schema.required,
+        Some(vec!["fullName".to_string(), "years".to_string()])
+    );
+    assert_eq!(
+        property(&schema, "fullName").description.as_deref(),
+        Some("Their full name.")
+    );
+    assert_eq!(property(&schema, "years").format.as_deref(), Some("int32"));
+    assert_eq!(property(&schema, "nickname").nullable, Some(true));
+    assert_eq!(property(&schema, "tags").r#type, Some(Type::Array));
+}
+
+#[derive(Serialize, Deserialize, GenAiSchema, Default)]
+#[serde(default)]
+#[allow(dead_code)]
+struct AllDefault {
+    a: u32,
+    b: String,
+}
+
+#[test]
+fn container_default_makes_every_field_optional() {
+    assert_eq!(AllDefault::schema().required, None);
+}
+
+#[derive(Serialize, Deserialize, GenAiSchema)]
+#[allow(dead_code)]
+struct Page<T> {
+    items: Vec<T>,
+    next: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, GenAiSchema)]
+#[allow(dead_code)]
+struct Wrapper(HashMap<String, f64>);
+
+#[derive(Serialize, Deserialize, GenAiSchema)]
+#[serde(transparent)]
+#[allow(dead_code)]
+struct Meters {
+    value: f32,
+}
+
+#[test]
+fn generic_and_newtype_structs() {
+    let schema = Page::<Person>::schema();
+    let items = property(&schema, "items").items.as_ref().unwrap();
+    assert_eq!(items.description.as_deref(), Some("A person."));
+    assert_eq!(schema.required, Some(vec!["items".to_string()]));
+
+    assert_eq!(Wrapper::schema().r#type, Some(Type::Object));
+    assert!(Wrapper::schema().properties.is_none());
+    assert_eq!(Meters::schema().r#type, Some(Type::Number));
+    assert_eq!(Meters::schema().format.as_deref(), Some("float"));
+}
+
+#[derive(Serialize, Deserialize, GenAiSchema)]
+#[allow(dead_code)]
+enum Status {
+    Active,
+    #[serde(rename = "on-hold")]
+    OnHold,
+    #[serde(skip)]
+    Internal,
+    #[serde(other)]
+    Unknown,
+}
+
+#[test]
+fn enum_variants() {
+    let schema = Status::schema();
+    assert_eq!(schema.r#type, Some(Type::String));
+    assert_eq!(
+        schema.r#enum,
+        Some(vec!["Active".to_string(), "on-hold".to_string()])
+    );
+}
+
+/// Adds two numbers.
+#[tool]
+fn add(
+    /// The first number.
+    a: i64,
+    b: i64,
+) -> i64 {
+    a + b
+}
+
+/// Looks up a user.
+#[tool(name = "lookup_user", description = "Finds a user by id.")]
+async fn find_user(id: u32, with_email: Option<bool>) -> Result<Person, String> {
+    if id == 0 {
+        return Err("No user with id 0".to_string());
+    }
+    Ok(Person {
+        full_name: "Ada".to_string(),
+        age: 36,
+        email: with_email
+            .unwrap_or_default()
+            .then(|| "ada@example.com".to_string()),
+        cache: Vec::new(),
+        tags: Vec::new(),
+        nickname: None,
+    })
+}
+
+/// Reports the time.
+#[tool]
+async fn now() -> String {
+    "noon".to_string()
+}
+
+#[tokio::test]
+async fn tool_on_sync_function() {
+    let declaration = add::declaration();
+    assert_eq!(declaration.name, "add");
+    assert_eq!(
+        declaration.description.as_deref(),
+        Some("Adds two numbers.")
+    );
+    let parameters = declaration.parameters.unwrap();
+    assert_eq!(property_names(&parameters), ["a", "b"]);
+    assert_eq!(
+        property(&parameters, "a").description.as_deref(),
+        Some("The first number.")
+    );
+    assert_eq!(
+        parameters.required,
+        Some(vec!["a".to_string(), "b".to_string()])
+    );
+
+    let result = add::call(serde_json::json!({"a": 2, "b": 3}))
+        .await
+        .unwrap();
+    assert_eq!(result, 5);
+    let err = add::call(serde_json::json!({"a": "two", "b": 3}))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Invalid argument `a`"), "{}", err);
+}
+
+#[tokio::test]
+async fn tool_on_async_result_function() {
+    let declaration = find_user::declaration();
+    assert_eq!(declaration.name, "lookup_user");
+    assert_eq!(
+        declaration.description.as_deref(),
+        Some("Finds a user by id.")
+    );
+    let parameters = declaration.parameters.unwrap();
+    assert_eq!(parameters.required, Some(vec!["id".to_string()]));
+
+    let result = find_user::call(serde_json::json!({"id": 7, "with_email": true}))
+        .await
+        .unwrap();
+    assert_eq!(result["fullName"], "Ada");
+    assert_eq!(result["mail_out"], "ada@example.com");
+    // Optional arguments may be left out.
+    let result = find_user::call(serde_json::json!({"id": 7})).await.unwrap();
+    assert_eq!(result["mail_out"], serde_json::Value::Null);
+
+    let err = find_user::call(serde_json::json!({"id": 0}))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().ends_with("No user with id 0"), "{}", err);
+}
+
+#[tokio::test]
+async fn tool_without_arguments() {
+    let declaration = now::declaration();
+    assert!(declaration.parameters.is_none());
+    let result = now::call(serde_json::json!({})).await.unwrap();
+    assert_eq!(result, "noon");
+}
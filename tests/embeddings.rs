@@ -0,0 +1,104 @@
+use google_genai::client::MAX_BATCH_EMBED_REQUESTS;
+use google_genai::datatypes::{
+    BatchEmbedContentsResponse, Content, ContentEmbedding, EmbedContentReq,
+};
+use google_genai::error::ErrorKind;
+use google_genai::testing::{MockReply, MockServer};
+use serde_json::Value;
+
+/// A batch response whose embeddings hold the indexes `range` of the overall input.
+fn embeddings(range: std::ops::Range<usize>) -> MockReply {
+    MockReply::json(BatchEmbedContentsResponse {
+        embeddings: range
+            .map(|i| ContentEmbedding {
+                values: vec![i as f32],
+            })
+            .collect(),
+    })
+}
+
+#[tokio::test]
+async fn embed_content_returns_the_embedding() {
+    let server = MockServer::start().await;
+    server.reply_to(
+        "embedContent",
+        MockReply::json(serde_json::json!({ "embedding": { "values": [0.5, -0.25] } })),
+    );
+
+    let embedding = server
+        .client()
+        .embed_content(
+            EmbedContentReq::default()
+                .model("text-embedding-004")
+                .content(Content::user_text("Hi")),
+        )
+        .await
+        .unwrap();
+    assert_eq!(embedding.values, vec![0.5, -0.25]);
+
+    let sent = server.last_request();
+    assert_eq!(sent.path, "/v1beta/models/text-embedding-004:embedContent");
+    let body: Value = sent.json();
+    assert_eq!(body["model"], "models/text-embedding-004");
+}
+
+#[tokio::test]
+async fn large_batches_are_split_and_kept_in_order() {
+    let server = MockServer::start().await;
+    server
+        .reply_to("batchEmbedContents", embeddings(0..100))
+        .reply_to("batchEmbedContents", embeddings(100..200))
+        .reply_to("batchEmbedContents", embeddings(200..250));
+    let reqs = (0..250)
+        .map(|i| EmbedContentReq::default().content(Content::user_text(i.to_string())))
+        .collect();
+
+    let result = server
+        .client()
+        .batch_embed_contents("text-embedding-004", reqs)
+        .await
+        .unwrap();
+
+    assert_eq!(result.len(), 250);
+    for (i, embedding) in result.iter().enumerate() {
+        assert_eq!(embedding.values, vec![i as f32]);
+    }
+
+    let sent = server.requests_to("batchEmbedContents");
+    assert_eq!(sent.len(), 3);
+    let mut next = 0;
+    for (request, size) in sent.iter().zip([MAX_BATCH_EMBED_REQUESTS, 100, 50]) {
+        assert_eq!(
+            request.path,
+            "/v1beta/models/text-embedding-004:batchEmbedContents"
+        );
+        let body: Value = request.json();
+        let requests = body["requests"].as_array().unwrap();
+        assert_eq!(requests.len(), size);
+        for req in requests {
+            assert_eq!(req["model"], "models/text-embedding-004");
+            assert_eq!(req["content"]["parts"][0]["text"], next.to_string());
+            next += 1;
+        }
+    }
+    assert_eq!(next, 250);
+}
+
+#[tokio::test]
+async fn batches_with_missing_embeddings_fail() {
+    let server = MockServer::start().await;
+    server.reply_to("batchEmbedContents", embeddings(0..1));
+    let reqs = vec![
+        EmbedContentReq::default().content(Content::user_text("a")),
+        EmbedContentReq::default().content(Content::user_text("b")),
+    ];
+
+    let err = server
+        .client()
+        .batch_embed_contents("text-embedding-004", reqs)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Decode);
+    let source = std::error::Error::source(&err).unwrap();
+    assert_eq!(source.to_string(), "Expected 2 embeddings, got 1");
+}
@@ -0,0 +1,134 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use google_genai::datatypes::{File, FileState};
+use google_genai::error::{ErrorKind, GenAiError};
+use google_genai::files::UPLOAD_CHUNK_GRANULARITY;
+use google_genai::testing::{MockReply, MockServer};
+use google_genai::UploadFileConfig;
+
+/// Writes a file a little over one upload chunk long, unique to this test.
+fn two_chunk_file(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("google-genai-files-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    let data: Vec<u8> = (0..UPLOAD_CHUNK_GRANULARITY + 1000)
+        .map(|i| i as u8)
+        .collect();
+    std::fs::write(&path, data).unwrap();
+    path
+}
+
+fn config() -> UploadFileConfig {
+    UploadFileConfig::default()
+        .mime_type("application/octet-stream")
+        .chunk_size(UPLOAD_CHUNK_GRANULARITY)
+}
+
+fn session_started(server: &MockServer) -> MockReply {
+    MockReply::json(serde_json::json!({})).header(
+        "x-goog-upload-url",
+        format!("{}/upload-session", server.base_url()),
+    )
+}
+
+fn uploaded_file() -> File {
+    File::default()
+        .name("files/abc-123")
+        .state(FileState::Active)
+}
+
+#[tokio::test]
+async fn failed_chunks_resume_from_the_reported_offset() {
+    let path = two_chunk_file("resume.bin");
+    let server = MockServer::start().await;
+    server.reply(session_started(&server));
+    server.reply(MockReply::error(503, "chunk failed"));
+    server.reply(
+        MockReply::json(serde_json::json!({}))
+            .header("x-goog-upload-status", "active")
+            .header("x-goog-upload-size-received", "1000"),
+    );
+    server.reply(MockReply::json(
+        serde_json::json!({ "file": uploaded_file() }),
+    ));
+
+    let file = server.client().upload_file(&path, config()).await.unwrap();
+    assert_eq!(file.name.as_deref(), Some("files/abc-123"));
+
+    let sent = server.requests();
+    let commands: Vec<_> = sent
+        .iter()
+        .map(|r| r.header("x-goog-upload-command").unwrap())
+        .collect();
+    assert_eq!(commands, ["start", "upload", "query", "upload, finalize"]);
+    assert_eq!(sent[1].header("x-goog-upload-offset"), Some("0"));
+    assert_eq!(sent[3].header("x-goog-upload-offset"), Some("1000"));
+    // The rest of the file fits in one chunk.
+    assert_eq!(sent[3].body.len(), UPLOAD_CHUNK_GRANULARITY);
+    assert_eq!(sent[3].body[0], (1000 % 256) as u8);
+}
+
+#[tokio::test]
+async fn failed_queries_count_against_the_failure_budget() {
+    let path = two_chunk_file("budget.bin");
+    let server = MockServer::start().await;
+    server.reply(session_started(&server));
+    server.reply(MockReply::error(503, "chunk failed"));
+    server.reply(MockReply::error(500, "query failed"));
+    server.reply(MockReply::error(500, "query failed"));
+
+    let err = server
+        .client()
+        .upload_file(&path, config())
+        .await
+        .unwrap_err();
+    match &err {
+        GenAiError::Remote {
+            status, message, ..
+        } => {
+            assert_eq!(*status, 503);
+            assert_eq!(message, "chunk failed");
+        }
+        other => panic!("expected the chunk's error, got {:?}", other),
+    }
+    assert_eq!(server.requests().len(), 4);
+    server.assert_all_replies_used();
+}
+
+#[tokio::test]
+async fn failed_processing_returns_the_file_error() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::json(
+        File::default()
+            .name("files/abc-123")
+            .state(FileState::Processing),
+    ));
+    server.reply(MockReply::json(serde_json::json!({
+        "name": "files/abc-123",
+        "state": "FAILED",
+        "error": { "code": 3, "message": "Unsupported video codec" },
+    })));
+
+    let err = server
+        .client()
+        .wait_for_file_active("abc-123", Duration::from_millis(10), Duration::from_secs(5))
+        .await
+        .unwrap_err();
+    match &err {
+        GenAiError::FileFailed { name, error } => {
+            assert_eq!(name, "files/abc-123");
+            assert_eq!(error.as_ref().unwrap().code, Some(3));
+        }
+        other => panic!("expected FileFailed, got {:?}", other),
+    }
+    assert_eq!(err.kind(), ErrorKind::Remote);
+    assert!(!err.is_retryable());
+    assert_eq!(
+        err.to_string(),
+        "Processing of files/abc-123 failed: Unsupported video codec"
+    );
+    assert_eq!(err.api_error().unwrap().code, Some(3));
+    assert_eq!(server.requests().len(), 2);
+    assert_eq!(server.last_request().path, "/v1beta/files/abc-123");
+}
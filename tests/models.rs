@@ -0,0 +1,65 @@
+use futures_util::TryStreamExt;
+use google_genai::testing::{MockReply, MockServer};
+use serde_json::json;
+
+#[tokio::test]
+async fn list_models_follows_page_tokens() {
+    let server = MockServer::start().await;
+    server
+        .reply(MockReply::json(json!({
+            "models": [
+                { "name": "models/gemini-2.0-flash" },
+                { "name": "models/gemini-2.0-flash-lite" },
+            ],
+            "nextPageToken": "page-2",
+        })))
+        .reply(MockReply::json(json!({
+            "models": [{ "name": "models/text-embedding-004" }],
+        })));
+
+    let models: Vec<_> = server
+        .client()
+        .list_models(Some(2))
+        .try_collect()
+        .await
+        .unwrap();
+    let names: Vec<_> = models.iter().filter_map(|m| m.name.as_deref()).collect();
+    assert_eq!(
+        names,
+        [
+            "models/gemini-2.0-flash",
+            "models/gemini-2.0-flash-lite",
+            "models/text-embedding-004",
+        ]
+    );
+
+    let sent = server.requests();
+    assert_eq!(sent.len(), 2);
+    for request in &sent {
+        assert_eq!(request.method, "GET");
+        assert!(
+            request.path.starts_with("/v1beta/models?"),
+            "{}",
+            request.path
+        );
+        assert_eq!(request.query("pageSize"), Some("2"));
+    }
+    assert_eq!(sent[0].query("pageToken"), None);
+    assert_eq!(sent[1].query("pageToken"), Some("page-2"));
+}
+
+#[tokio::test]
+async fn get_model_fetches_by_qualified_name() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::json(json!({
+        "name": "models/gemini-2.0-flash",
+        "inputTokenLimit": 1048576,
+    })));
+
+    let model = server.client().get_model("gemini-2.0-flash").await.unwrap();
+    assert_eq!(model.name.as_deref(), Some("models/gemini-2.0-flash"));
+    assert_eq!(
+        server.last_request().path,
+        "/v1beta/models/gemini-2.0-flash"
+    );
+}
@@ -0,0 +1,167 @@
+use futures_util::StreamExt;
+use google_genai::auth::AuthorizedUser;
+use google_genai::error::{ErrorKind, GenAiError};
+use google_genai::testing::{request, MockReply, MockServer, TEST_API_KEY};
+use google_genai::Client;
+
+/// Asserts that neither the `Display` nor the `Debug` output of `err` contains `secret`.
+fn assert_redacted(err: &GenAiError, secret: &str) {
+    let display = err.to_string();
+    let debug = format!("{:?}", err);
+    assert!(!display.contains(secret), "secret in Display: {}", display);
+    assert!(!debug.contains(secret), "secret in Debug: {}", debug);
+    assert!(
+        display.contains("[REDACTED]") || debug.contains("[REDACTED]"),
+        "no placeholder in {}",
+        debug
+    );
+}
+
+#[tokio::test]
+async fn api_key_is_sent_as_a_header() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::text("ok"));
+    server.client().generate_content(request()).await.unwrap();
+
+    let sent = server.last_request();
+    assert_eq!(sent.header("x-goog-api-key"), Some(TEST_API_KEY));
+    assert!(!sent.path.contains(TEST_API_KEY));
+}
+
+#[tokio::test]
+async fn remote_errors_are_redacted() {
+    let server = MockServer::start().await;
+    server.reply(
+        MockReply::error(400, format!("API key not valid: {}", TEST_API_KEY))
+            .header("x-echo", TEST_API_KEY),
+    );
+    let err = server
+        .client()
+        .generate_content(request())
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Remote);
+    assert_redacted(&err, TEST_API_KEY);
+}
+
+#[tokio::test]
+async fn error_details_are_redacted() {
+    let server = MockServer::start().await;
+    server.reply(
+        MockReply::json(serde_json::json!({
+            "error": {
+                "code": 400,
+                "message": "API key not valid.",
+                "status": format!("INVALID_ARGUMENT {}", TEST_API_KEY),
+                "details": [
+                    {
+                        "@type": "type.googleapis.com/google.rpc.ErrorInfo",
+                        "reason": "API_KEY_INVALID",
+                        "domain": "googleapis.com",
+                        "metadata": { "key": TEST_API_KEY },
+                    },
+                    {
+                        "@type": "type.googleapis.com/google.rpc.Help",
+                        "links": [{ "url": format!("https://example.com/?key={}", TEST_API_KEY) }],
+                    },
+                ],
+            }
+        }))
+        .status(400),
+    );
+    let err = server
+        .client()
+        .generate_content(request())
+        .await
+        .unwrap_err();
+    assert_redacted(&err, TEST_API_KEY);
+    let api_error = err.api_error().unwrap();
+    assert_eq!(api_error.details.len(), 2);
+    assert_eq!(
+        api_error
+            .error_info()
+            .unwrap()
+            .metadata
+            .get("key")
+            .map(String::as_str),
+        Some("[REDACTED]")
+    );
+}
+
+#[tokio::test]
+async fn decode_errors_are_redacted() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::raw(format!(
+        "<html>bad key {}</html>",
+        TEST_API_KEY
+    )));
+    let err = server
+        .client()
+        .generate_content(request())
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Decode);
+    assert_redacted(&err, TEST_API_KEY);
+}
+
+#[tokio::test]
+async fn stream_errors_are_redacted() {
+    let server = MockServer::start().await;
+    server
+        .reply(MockReply::stream([]).stream_error(500, format!("failed for key {}", TEST_API_KEY)));
+    let mut stream = server
+        .client()
+        .generate_content_stream(request())
+        .await
+        .unwrap();
+    let err = stream.next().await.unwrap().unwrap_err();
+    assert_redacted(&err, TEST_API_KEY);
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn auth_errors_are_redacted() {
+    const CLIENT_SECRET: &str = "client-secret-value";
+    const REFRESH_TOKEN: &str = "refresh-token-value";
+
+    let server = MockServer::start().await;
+    server.reply(MockReply::error(
+        400,
+        format!(
+            "invalid_grant: {} for client secret {}",
+            REFRESH_TOKEN, CLIENT_SECRET
+        ),
+    ));
+    let user = AuthorizedUser::new("client-id", CLIENT_SECRET, REFRESH_TOKEN)
+        .token_uri(format!("{}/token", server.base_url()))
+        .http_client(server.http_client());
+    let client = Client::builder()
+        .base_url(server.base_url())
+        .http_client(server.http_client())
+        .authenticator(user)
+        .build()
+        .unwrap();
+
+    let err = client.generate_content(request()).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Auth);
+    assert_redacted(&err, CLIENT_SECRET);
+    assert_redacted(&err, REFRESH_TOKEN);
+}
+
+#[test]
+fn error_sources_are_kept_unless_they_hold_the_secret() {
+    use std::error::Error;
+
+    let stream_error = |detail: String| GenAiError::Stream {
+        message: "Response stream is not a valid event stream".to_string(),
+        source: Some(Box::new(std::io::Error::other(detail))),
+    };
+
+    let err = stream_error("unexpected line".to_string()).redact(TEST_API_KEY);
+    assert_eq!(err.kind(), ErrorKind::Stream);
+    assert_eq!(err.source().unwrap().to_string(), "unexpected line");
+
+    let err = stream_error(format!("unexpected line with {}", TEST_API_KEY)).redact(TEST_API_KEY);
+    assert!(err.source().is_none());
+    assert!(!format!("{:?}", err).contains(TEST_API_KEY));
+}
@@ -0,0 +1,158 @@
+use std::time::{Duration, Instant};
+
+use futures_util::StreamExt;
+use google_genai::error::{ErrorKind, GenAiError};
+use google_genai::testing::{request, MockReply, MockServer};
+use google_genai::{Client, RetryPolicy};
+
+fn retrying_client(server: &MockServer, policy: RetryPolicy) -> Client {
+    server
+        .client_builder()
+        .retry_policy(policy)
+        .build()
+        .unwrap()
+}
+
+/// A policy with short, predictable delays.
+fn fast_policy() -> RetryPolicy {
+    RetryPolicy::default()
+        .base_delay(Duration::from_millis(10))
+        .max_delay(Duration::from_secs(2))
+        .jitter(0.0)
+}
+
+fn status(err: &GenAiError) -> Option<u16> {
+    match err {
+        GenAiError::Remote { status, .. } => Some(*status),
+        _ => None,
+    }
+}
+
+/// A 429 whose body carries a `RetryInfo` detail.
+fn quota_exceeded(retry_delay: &str) -> MockReply {
+    MockReply::json(serde_json::json!({
+        "error": {
+            "code": 429,
+            "message": "Resource has been exhausted",
+            "status": "RESOURCE_EXHAUSTED",
+            "details": [{
+                "@type": "type.googleapis.com/google.rpc.RetryInfo",
+                "retryDelay": retry_delay,
+            }],
+        }
+    }))
+    .status(429)
+}
+
+#[tokio::test]
+async fn transient_statuses_are_retried() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::error(429, "slow down"));
+    server.reply(MockReply::error(503, "unavailable"));
+    server.reply(MockReply::text("ok"));
+    let client = retrying_client(&server, fast_policy());
+
+    let response = client.generate_content(request()).await.unwrap();
+    assert_eq!(response.text().as_deref(), Some("ok"));
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn retries_stop_after_max_attempts() {
+    let server = MockServer::start().await;
+    for _ in 0..3 {
+        server.reply(MockReply::error(503, "unavailable"));
+    }
+    let client = retrying_client(&server, fast_policy().max_attempts(3u32));
+
+    let err = client.generate_content(request()).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Remote);
+    assert_eq!(status(&err), Some(503));
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn other_statuses_are_not_retried() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::error(400, "bad request"));
+    let client = retrying_client(&server, fast_policy());
+
+    let err = client.generate_content(request()).await.unwrap_err();
+    assert_eq!(status(&err), Some(400));
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn retry_after_header_sets_the_delay() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::error(503, "unavailable").header("Retry-After", "1"));
+    server.reply(MockReply::text("ok"));
+    let client = retrying_client(&server, fast_policy());
+
+    let start = Instant::now();
+    client.generate_content(request()).await.unwrap();
+    assert!(
+        start.elapsed() >= Duration::from_secs(1),
+        "{:?}",
+        start.elapsed()
+    );
+}
+
+#[tokio::test]
+async fn retry_info_sets_the_delay() {
+    let server = MockServer::start().await;
+    server.reply(quota_exceeded("0.5s"));
+    server.reply(MockReply::text("ok"));
+    let client = retrying_client(&server, fast_policy());
+
+    let start = Instant::now();
+    client.generate_content(request()).await.unwrap();
+    assert!(
+        start.elapsed() >= Duration::from_millis(500),
+        "{:?}",
+        start.elapsed()
+    );
+}
+
+#[tokio::test]
+async fn hints_beyond_max_delay_end_the_retries() {
+    let server = MockServer::start().await;
+    server.reply(quota_exceeded("3600s"));
+    server.reply(MockReply::text("ok"));
+    let client = retrying_client(&server, fast_policy());
+
+    let start = Instant::now();
+    let err = client.generate_content(request()).await.unwrap_err();
+    assert_eq!(status(&err), Some(429));
+    assert_eq!(err.retry_delay(), Some(Duration::from_secs(3600)));
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn stream_connection_is_retried() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::error(503, "unavailable"));
+    server.reply(MockReply::text_stream(["o", "k"]));
+    let client = retrying_client(&server, fast_policy());
+
+    let stream = client.generate_content_stream(request()).await.unwrap();
+    let chunks: Vec<_> = stream.collect().await;
+    assert_eq!(chunks.len(), 2);
+    assert!(chunks.iter().all(Result::is_ok));
+    assert_eq!(server.requests_to("streamGenerateContent").len(), 2);
+}
+
+#[test]
+fn default_policy_agrees_with_is_retryable() {
+    let policy = RetryPolicy::default();
+    for status in 400..600 {
+        let err = GenAiError::remote(status, Default::default(), String::new());
+        assert_eq!(
+            policy.is_retryable_status(status),
+            err.is_retryable(),
+            "status {}",
+            status
+        );
+    }
+}
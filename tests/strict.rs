@@ -0,0 +1,141 @@
+use futures_util::StreamExt;
+use google_genai::datatypes::{BlockedReason, FinishReason, GenerateContentResponse};
+use google_genai::error::{GenAiError, Result};
+use google_genai::testing::{request, MockReply, MockServer};
+use google_genai::Client;
+use serde_json::json;
+
+fn client(server: &MockServer, strict: bool) -> Client {
+    server.client_builder().strict(strict).build().unwrap()
+}
+
+fn blocked_prompt() -> MockReply {
+    MockReply::json(json!({
+        "promptFeedback": { "blockReason": "SAFETY" },
+    }))
+}
+
+fn blocked_candidate() -> MockReply {
+    MockReply::json(json!({
+        "candidates": [{ "index": 0, "finishReason": "SAFETY" }],
+    }))
+}
+
+fn chunk(value: serde_json::Value) -> GenerateContentResponse {
+    serde_json::from_value(value).unwrap()
+}
+
+/// A stream whose second chunk is stopped by the safety filters.
+fn stream_blocked_midway() -> MockReply {
+    MockReply::stream([
+        chunk(json!({
+            "candidates": [{
+                "content": { "role": "model", "parts": [{ "text": "Once upon" }] },
+                "index": 0,
+            }],
+        })),
+        chunk(json!({
+            "candidates": [{
+                "index": 0,
+                "finishReason": "SAFETY",
+                "safetyRatings": [{ "category": "HARM_CATEGORY_HARASSMENT", "probability": "HIGH" }],
+            }],
+        })),
+    ])
+}
+
+#[tokio::test]
+async fn strict_generate_content_returns_blocked() {
+    let server = MockServer::start().await;
+    server
+        .reply(blocked_prompt())
+        .reply(blocked_candidate())
+        .reply(MockReply::json(json!({})));
+    let client = client(&server, true);
+
+    match client.generate_content(request()).await.unwrap_err() {
+        GenAiError::Blocked {
+            block_reason,
+            finish_reason,
+            ..
+        } => {
+            assert_eq!(block_reason, Some(BlockedReason::Safety));
+            assert_eq!(finish_reason, None);
+        }
+        other => panic!("expected Blocked, got {:?}", other),
+    }
+    match client.generate_content(request()).await.unwrap_err() {
+        GenAiError::Blocked {
+            block_reason,
+            finish_reason,
+            ..
+        } => {
+            assert_eq!(block_reason, None);
+            assert_eq!(finish_reason, Some(FinishReason::Safety));
+        }
+        other => panic!("expected Blocked, got {:?}", other),
+    }
+    let err = client.generate_content(request()).await.unwrap_err();
+    assert!(matches!(err, GenAiError::Blocked { .. }), "{:?}", err);
+}
+
+#[tokio::test]
+async fn lenient_generate_content_returns_blocked_responses() {
+    let server = MockServer::start().await;
+    server
+        .reply(blocked_prompt())
+        .reply(blocked_candidate())
+        .reply(MockReply::json(json!({})));
+    let client = client(&server, false);
+
+    let response = client.generate_content(request()).await.unwrap();
+    assert_eq!(response.blocked_reason(), Some(&BlockedReason::Safety));
+    let response = client.generate_content(request()).await.unwrap();
+    assert_eq!(response.finish_reason(), Some(&FinishReason::Safety));
+    assert!(response.is_blocked());
+    let response = client.generate_content(request()).await.unwrap();
+    assert!(response.candidate().is_none());
+}
+
+#[tokio::test]
+async fn strict_streams_return_blocked_on_a_safety_chunk() {
+    let server = MockServer::start().await;
+    server.reply_to("streamGenerateContent", stream_blocked_midway());
+
+    let stream = client(&server, true)
+        .generate_content_stream(request())
+        .await
+        .unwrap();
+    let items: Vec<Result<GenerateContentResponse>> = stream.collect().await;
+    assert_eq!(items.len(), 2);
+    assert_eq!(
+        items[0].as_ref().unwrap().text().as_deref(),
+        Some("Once upon")
+    );
+    match &items[1] {
+        Err(GenAiError::Blocked {
+            finish_reason,
+            safety_ratings,
+            ..
+        }) => {
+            assert_eq!(finish_reason, &Some(FinishReason::Safety));
+            assert_eq!(safety_ratings.len(), 1);
+        }
+        other => panic!("expected Blocked, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn lenient_streams_pass_safety_chunks_through() {
+    let server = MockServer::start().await;
+    server.reply_to("streamGenerateContent", stream_blocked_midway());
+
+    let stream = client(&server, false)
+        .generate_content_stream(request())
+        .await
+        .unwrap();
+    let items: Vec<Result<GenerateContentResponse>> = stream.collect().await;
+    assert_eq!(items.len(), 2);
+    let last = items[1].as_ref().unwrap();
+    assert_eq!(last.finish_reason(), Some(&FinishReason::Safety));
+}
@@ -0,0 +1,113 @@
+use google_genai::datatypes::{
+    BlockedReason, Candidate, FinishReason, GenerateContentReq, GenerateContentResponse,
+};
+use google_genai::error::GenAiError;
+use google_genai::testing::{request, MockReply, MockServer};
+use google_genai::GenAiSchema;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, GenAiSchema, PartialEq)]
+struct Recipe {
+    name: String,
+    minutes: u32,
+}
+
+#[tokio::test]
+async fn output_is_parsed_with_the_schema_requested() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::text(r#"{"name": "Toast", "minutes": 3}"#));
+
+    let recipe: Recipe = server.client().generate_json(request()).await.unwrap();
+    assert_eq!(
+        recipe,
+        Recipe {
+            name: "Toast".to_string(),
+            minutes: 3
+        }
+    );
+    let sent: serde_json::Value = server.last_request().json();
+    let config = &sent["generationConfig"];
+    assert_eq!(config["responseMimeType"], "application/json");
+    assert_eq!(config["responseSchema"]["required"][1], "minutes");
+}
+
+#[tokio::test]
+async fn unparseable_output_is_repaired() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::text(r#"{"name": "Toast"}"#));
+    server.reply(MockReply::text(r#"{"name": "Toast", "minutes": 3}"#));
+
+    let recipe: Recipe = server
+        .client()
+        .generate_json_with_repair(request(), 1)
+        .await
+        .unwrap();
+    assert_eq!(recipe.minutes, 3);
+
+    let sent: GenerateContentReq = server.last_request().json();
+    assert_eq!(sent.contents.len(), 3);
+    assert_eq!(sent.contents[1].role.as_deref(), Some("model"));
+    let repair = sent.contents[2].parts.as_ref().unwrap()[0]
+        .text
+        .as_deref()
+        .unwrap();
+    assert!(repair.contains("missing field `minutes`"), "{}", repair);
+}
+
+#[tokio::test]
+async fn output_that_never_parses_is_an_error() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::text("Sure! Here's a recipe."));
+
+    let err = server
+        .client()
+        .generate_json::<Recipe>(request())
+        .await
+        .unwrap_err();
+    match err {
+        GenAiError::ParseOutput { text, .. } => assert_eq!(text, "Sure! Here's a recipe."),
+        other => panic!("expected ParseOutput, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn blocked_prompts_are_not_parsed_or_repaired() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::json(serde_json::json!({
+        "promptFeedback": { "blockReason": "SAFETY" },
+    })));
+
+    let err = server
+        .client()
+        .generate_json_with_repair::<Recipe>(request(), 2)
+        .await
+        .unwrap_err();
+    match err {
+        GenAiError::Blocked { block_reason, .. } => {
+            assert_eq!(block_reason, Some(BlockedReason::Safety))
+        }
+        other => panic!("expected Blocked, got {:?}", other),
+    }
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn blocked_responses_are_not_parsed_or_repaired() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::response(
+        GenerateContentResponse::default().candidates(vec![Candidate::default()
+            .finish_reason(FinishReason::Safety)
+            .index(0)]),
+    ));
+    server.reply(MockReply::response(GenerateContentResponse::default()));
+
+    for _ in 0..2 {
+        let err = server
+            .client()
+            .generate_json_with_repair::<Recipe>(request(), 2)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, GenAiError::Blocked { .. }), "{:?}", err);
+    }
+    assert_eq!(server.requests().len(), 2);
+}
@@ -0,0 +1,216 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures_util::future::BoxFuture;
+use google_genai::datatypes::{
+    Candidate, Content, FinishReason, FunctionCall, FunctionResponse, GenerateContentReq,
+    GenerateContentResponse, Part,
+};
+use google_genai::error::ErrorKind;
+use google_genai::testing::{request, MockReply, MockServer};
+use google_genai::{tool, ToolHooks, ToolRegistry};
+
+/// Looks up the weather in a city.
+#[tool]
+async fn get_weather(city: String) -> Result<String, String> {
+    if city.is_empty() {
+        return Err("No city given".to_string());
+    }
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    Ok(format!("Sunny in {}", city))
+}
+
+/// Deletes a file.
+#[tool]
+fn delete_file(path: String) -> bool {
+    panic!("{} should not have been deleted", path)
+}
+
+fn registry() -> ToolRegistry {
+    ToolRegistry::new()
+        .register(get_weather::declaration(), get_weather::call)
+        .register(delete_file::declaration(), delete_file::call)
+}
+
+/// A model turn that calls functions.
+fn calls(calls: &[(&str, serde_json::Value)]) -> MockReply {
+    let parts = calls
+        .iter()
+        .enumerate()
+        .map(|(i, (name, args))| {
+            Part::default().function_call(
+                FunctionCall::default()
+                    .id(format!("call-{}", i))
+                    .name(*name)
+                    .args(args.clone()),
+            )
+        })
+        .collect();
+    MockReply::response(
+        GenerateContentResponse::default().candidates(vec![Candidate::default()
+            .content(Content::new("model", parts))
+            .finish_reason(FinishReason::Stop)]),
+    )
+}
+
+/// The function responses sent back to the model in the last request.
+fn sent_responses(server: &MockServer) -> Vec<FunctionResponse> {
+    let req: GenerateContentReq = server.last_request().json();
+    req.contents
+        .last()
+        .unwrap()
+        .parts
+        .iter()
+        .flatten()
+        .map(|p| p.function_response.clone().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn parallel_calls_run_concurrently() {
+    let server = MockServer::start().await;
+    server.reply(calls(&[
+        ("get_weather", serde_json::json!({"city": "Paris"})),
+        ("get_weather", serde_json::json!({"city": "London"})),
+    ]));
+    server.reply(MockReply::text("Sunny in both."));
+
+    let start = Instant::now();
+    let result = server
+        .client()
+        .generate_content_with_tools(request(), &registry())
+        .await
+        .unwrap();
+    assert!(
+        start.elapsed() < Duration::from_millis(390),
+        "{:?}",
+        start.elapsed()
+    );
+    assert_eq!(result.response.text().as_deref(), Some("Sunny in both."));
+
+    let responses = sent_responses(&server);
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0].id.as_deref(), Some("call-0"));
+    assert_eq!(
+        responses[0].response,
+        serde_json::json!({"result": "Sunny in Paris"})
+    );
+    assert_eq!(responses[1].id.as_deref(), Some("call-1"));
+    assert_eq!(
+        responses[1].response,
+        serde_json::json!({"result": "Sunny in London"})
+    );
+
+    // The user prompt, the model's calls and the responses.
+    assert_eq!(result.contents.len(), 3);
+    assert_eq!(result.contents[1].role.as_deref(), Some("model"));
+    let sent: GenerateContentReq = server.requests()[0].json();
+    let declared = sent.tools.unwrap()[0]
+        .function_declarations
+        .clone()
+        .unwrap();
+    assert_eq!(declared.len(), 2);
+}
+
+#[tokio::test]
+async fn handler_errors_reach_the_model_unwrapped() {
+    let server = MockServer::start().await;
+    server.reply(calls(&[
+        ("get_weather", serde_json::json!({"city": ""})),
+        ("get_weather", serde_json::json!({})),
+        ("get_time", serde_json::json!({})),
+    ]));
+    server.reply(MockReply::text("Sorry."));
+
+    server
+        .client()
+        .generate_content_with_tools(request(), &registry())
+        .await
+        .unwrap();
+
+    let responses = sent_responses(&server);
+    assert_eq!(
+        responses[0].response,
+        serde_json::json!({"error": "No city given"})
+    );
+    let missing = responses[1].response["error"].as_str().unwrap();
+    assert!(
+        missing.starts_with("Invalid argument `city`"),
+        "{}",
+        missing
+    );
+    assert_eq!(
+        responses[2].response,
+        serde_json::json!({"error": "Unknown function: get_time"})
+    );
+}
+
+#[tokio::test]
+async fn loop_gives_up_after_max_iterations() {
+    let server = MockServer::start().await;
+    for _ in 0..2 {
+        server.reply(calls(&[(
+            "get_weather",
+            serde_json::json!({"city": "Paris"}),
+        )]));
+    }
+
+    let err = server
+        .client()
+        .generate_content_with_tools(request(), &registry().max_iterations(2))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Internal);
+    assert!(err.to_string().contains("after 2 iterations"), "{}", err);
+    assert_eq!(server.requests().len(), 2);
+}
+
+/// Rejects deletions and records every response.
+#[derive(Default)]
+struct NoDeletes {
+    seen: Arc<Mutex<Vec<(String, serde_json::Value)>>>,
+}
+
+impl ToolHooks for NoDeletes {
+    fn approve<'a>(&'a self, call: &'a FunctionCall) -> BoxFuture<'a, bool> {
+        Box::pin(async move { call.name != "delete_file" })
+    }
+
+    fn on_response(&self, call: &FunctionCall, response: &FunctionResponse) {
+        self.seen
+            .lock()
+            .unwrap()
+            .push((call.name.clone(), response.response.clone()));
+    }
+}
+
+#[tokio::test]
+async fn hooks_can_reject_calls() {
+    let server = MockServer::start().await;
+    server.reply(calls(&[
+        ("delete_file", serde_json::json!({"path": "/etc/passwd"})),
+        ("get_weather", serde_json::json!({"city": "Paris"})),
+    ]));
+    server.reply(MockReply::text("I can't delete that."));
+    let hooks = NoDeletes::default();
+    let seen = hooks.seen.clone();
+
+    server
+        .client()
+        .generate_content_with_tools(request(), &registry().hooks(hooks))
+        .await
+        .unwrap();
+
+    let responses = sent_responses(&server);
+    assert_eq!(
+        responses[0].response,
+        serde_json::json!({"error": "Call was not approved"})
+    );
+    assert_eq!(
+        responses[1].response,
+        serde_json::json!({"result": "Sunny in Paris"})
+    );
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 2);
+    assert!(seen.iter().any(|(name, _)| name == "delete_file"));
+}
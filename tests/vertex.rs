@@ -0,0 +1,140 @@
+use futures_util::StreamExt;
+use google_genai::datatypes::{CachedContent, Content};
+use google_genai::error::ErrorKind;
+use google_genai::testing::{request, MockReply, MockServer};
+use google_genai::{Backend, Client};
+
+const TOKEN: &str = "vertex-access-token";
+
+fn vertex_client(server: &MockServer, location: &str) -> Client {
+    Client::builder()
+        .backend(Backend::vertex_ai("my-project", location))
+        .bearer_token(TOKEN)
+        .base_url(server.base_url())
+        .http_client(server.http_client())
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn generate_content_uses_vertex_path_and_bearer_token() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::text("ok"));
+    let client = vertex_client(&server, "us-central1");
+
+    let response = client.generate_content(request()).await.unwrap();
+    assert_eq!(response.text().as_deref(), Some("ok"));
+
+    let sent = server.last_request();
+    assert_eq!(sent.method, "POST");
+    assert_eq!(
+        sent.path,
+        "/v1/projects/my-project/locations/us-central1/publishers/google/models/\
+         gemini-2.0-flash:generateContent"
+    );
+    assert_eq!(
+        sent.header("authorization"),
+        Some(format!("Bearer {}", TOKEN).as_str())
+    );
+    assert_eq!(sent.header("x-goog-api-key"), None);
+}
+
+#[tokio::test]
+async fn global_location_and_qualified_model_names() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::text("ok"));
+    server.reply(MockReply::text("ok"));
+    let client = vertex_client(&server, "global");
+
+    client
+        .generate_content(request().model("publishers/google/models/gemini-2.0-flash"))
+        .await
+        .unwrap();
+    client
+        .generate_content(request().model("models/gemini-2.0-flash"))
+        .await
+        .unwrap();
+
+    for sent in server.requests() {
+        assert_eq!(
+            sent.path,
+            "/v1/projects/my-project/locations/global/publishers/google/models/\
+             gemini-2.0-flash:generateContent"
+        );
+    }
+}
+
+#[tokio::test]
+async fn stream_uses_vertex_path() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::text_stream(["o", "k"]));
+    let client = vertex_client(&server, "europe-west4");
+
+    let stream = client.generate_content_stream(request()).await.unwrap();
+    let chunks: Vec<_> = stream.collect().await;
+    assert_eq!(chunks.len(), 2);
+
+    let sent = server.last_request();
+    assert_eq!(
+        sent.path,
+        "/v1/projects/my-project/locations/europe-west4/publishers/google/models/\
+         gemini-2.0-flash:streamGenerateContent?alt=sse"
+    );
+    assert_eq!(sent.header("x-goog-api-key"), None);
+}
+
+#[tokio::test]
+async fn cached_content_names_the_vertex_model_resource() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::json(
+        serde_json::json!({"name": "cachedContents/1"}),
+    ));
+    let client = vertex_client(&server, "us-central1");
+
+    let cache = CachedContent::default()
+        .model("gemini-2.0-flash")
+        .contents(vec![Content::user_text("A long document")]);
+    client.create_cached_content(cache).await.unwrap();
+
+    let sent = server.last_request();
+    assert_eq!(
+        sent.path,
+        "/v1/projects/my-project/locations/us-central1/cachedContents"
+    );
+    let body: serde_json::Value = sent.json();
+    assert_eq!(
+        body["model"],
+        "projects/my-project/locations/us-central1/publishers/google/models/gemini-2.0-flash"
+    );
+}
+
+#[tokio::test]
+async fn gemini_only_endpoints_are_rejected() {
+    let server = MockServer::start().await;
+    let client = vertex_client(&server, "us-central1");
+
+    let err = client.get_model("gemini-2.0-flash").await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidRequest);
+    assert!(server.requests().is_empty());
+}
+
+#[tokio::test]
+async fn count_tokens_rejects_cached_content() {
+    let server = MockServer::start().await;
+    server.reply(MockReply::count_tokens(5));
+    let client = vertex_client(&server, "us-central1");
+
+    let err = client
+        .count_tokens(request().cached_content("cachedContents/1"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidRequest);
+    assert!(server.requests().is_empty());
+
+    let response = client.count_tokens(request()).await.unwrap();
+    assert_eq!(response.total_tokens, Some(5));
+    let sent = server.last_request();
+    assert!(sent.path.ends_with("gemini-2.0-flash:countTokens"));
+    let body: serde_json::Value = sent.json();
+    assert_eq!(body["contents"][0]["parts"][0]["text"], "Hi");
+}
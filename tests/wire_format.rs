@@ -0,0 +1,259 @@
+//! Round-trips synthetic API responses through the crate's types, so that a field missing
+//! from a struct or a misspelled wire name shows up as a difference. See
+//! `fixtures/README.md`.
+
+use google_genai::datatypes::{
+    BatchEmbedContentsResponse, BlockedReason, CachedContent, CountTokensResponse, File, FileState,
+    FinishReason, GenerateContentConfig, GenerateContentReq, GenerateContentResponse,
+    ListModelsResponse, Modality, ThinkingConfig,
+};
+use google_genai::StreamAccumulator;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+fn fixture(name: &str) -> Value {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    let json = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    serde_json::from_str(&json).unwrap()
+}
+
+/// Integers and floats compare by value, as `1` and `1.0` are the same number on the wire.
+fn normalize(value: Value) -> Value {
+    match value {
+        Value::Number(n) => serde_json::json!(n.as_f64().unwrap()),
+        Value::Array(items) => Value::Array(items.into_iter().map(normalize).collect()),
+        Value::Object(map) => {
+            Value::Object(map.into_iter().map(|(k, v)| (k, normalize(v))).collect())
+        }
+        other => other,
+    }
+}
+
+/// Parses a fixture as `T` and checks that serializing it again gives the same JSON.
+fn round_trip<T: DeserializeOwned + Serialize>(name: &str) -> T {
+    let expected = fixture(name);
+    let parsed: T = serde_json::from_value(expected.clone())
+        .unwrap_or_else(|e| panic!("{} does not parse: {}", name, e));
+    let written: Value = serde_json::from_str(&serde_json::to_string(&parsed).unwrap()).unwrap();
+    assert_eq!(
+        normalize(written),
+        normalize(expected),
+        "{} does not round-trip",
+        name
+    );
+    parsed
+}
+
+#[test]
+fn thinking_response() {
+    let response: GenerateContentResponse = round_trip("generate_content_thinking.json");
+    assert_eq!(
+        response.response_id.as_deref(),
+        Some("kP3yaN2hIbWOz7IPq4ahmQk")
+    );
+    assert_eq!(response.finish_reason(), Some(&FinishReason::Stop));
+    assert_eq!(
+        response.text().as_deref(),
+        Some("There are **3** r's in \"strawberry\".")
+    );
+    let parts = response
+        .candidate()
+        .unwrap()
+        .content
+        .as_ref()
+        .unwrap()
+        .parts
+        .as_ref()
+        .unwrap();
+    assert!(parts[0].is_thought());
+    assert!(parts[1].thought_signature.is_some());
+
+    let usage = response.usage_metadata.unwrap();
+    assert_eq!(usage.thoughts_token_count, Some(449));
+    let details = usage.prompt_tokens_details.unwrap();
+    assert_eq!(details[0].modality, Some(Modality::Text));
+    assert_eq!(details[0].token_count, Some(9));
+}
+
+#[test]
+fn function_call_response() {
+    let response: GenerateContentResponse = round_trip("generate_content_function_call.json");
+    let calls = response.function_calls();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[1].name, "get_weather");
+    assert_eq!(calls[1].args.as_ref().unwrap()["city"], "London");
+    assert_eq!(response.text(), None);
+}
+
+#[test]
+fn grounded_response() {
+    let response: GenerateContentResponse = round_trip("generate_content_grounding.json");
+    let grounding = response
+        .candidate()
+        .unwrap()
+        .grounding_metadata
+        .as_ref()
+        .unwrap();
+    assert_eq!(grounding.grounding_chunks.as_ref().unwrap().len(), 2);
+    let support = &grounding.grounding_supports.as_ref().unwrap()[0];
+    assert_eq!(support.grounding_chunk_indices, Some(vec![0, 1]));
+    assert_eq!(
+        response.usage_metadata.unwrap().tool_use_prompt_token_count,
+        Some(66)
+    );
+}
+
+#[test]
+fn blocked_prompt_response() {
+    let response: GenerateContentResponse = round_trip("generate_content_blocked_prompt.json");
+    assert!(response.is_blocked());
+    assert_eq!(response.blocked_reason(), Some(&BlockedReason::Safety));
+    assert!(response.candidates.is_none());
+}
+
+#[test]
+fn vertex_response() {
+    let response: GenerateContentResponse = round_trip("generate_content_vertex.json");
+    assert!(response.create_time.is_some());
+    let candidate = response.candidate().unwrap();
+    let citation = &candidate
+        .citation_metadata
+        .as_ref()
+        .unwrap()
+        .citations
+        .as_ref()
+        .unwrap()[0];
+    assert_eq!(citation.publication_date.unwrap().year(), 1891);
+    assert_eq!(candidate.safety_ratings.as_ref().unwrap().len(), 2);
+    assert_eq!(
+        response.usage_metadata.unwrap().traffic_type.as_deref(),
+        Some("ON_DEMAND")
+    );
+}
+
+#[test]
+fn streamed_thoughts_stay_apart_from_the_answer() {
+    let chunks: Vec<GenerateContentResponse> = round_trip("stream_thinking.json");
+    let mut acc = StreamAccumulator::new();
+    for chunk in &chunks {
+        acc.push(chunk);
+    }
+    let response = acc.finish();
+
+    assert_eq!(
+        response.text().as_deref(),
+        Some("Crimson leaves let go,\ndrifting on the cooling wind,\nthe year exhales slow.")
+    );
+    let parts = response
+        .candidate()
+        .unwrap()
+        .content
+        .as_ref()
+        .unwrap()
+        .parts
+        .clone()
+        .unwrap();
+    assert_eq!(parts.len(), 2);
+    assert!(parts[0].is_thought());
+    assert!(parts[0]
+        .text
+        .as_ref()
+        .unwrap()
+        .ends_with("cooling light.\n"));
+    assert!(!parts[1].is_thought());
+    assert_eq!(
+        parts[1].thought_signature.as_deref(),
+        Some("CiQBVKhc7sJ3Zy1nTGVhdmVzV2luZExpZ2h0QXV0dW1uSGFpa3U=")
+    );
+    assert_eq!(
+        response.response_id.as_deref(),
+        Some("pAHzaLnpM-SBz7IPgP3YmAs")
+    );
+    assert_eq!(
+        response.usage_metadata.unwrap().thoughts_token_count,
+        Some(298)
+    );
+}
+
+#[test]
+fn count_tokens_response() {
+    let response: CountTokensResponse = round_trip("count_tokens.json");
+    assert_eq!(response.total_tokens, Some(1290));
+    assert_eq!(
+        response.prompt_tokens_details.unwrap()[1].modality,
+        Some(Modality::Image)
+    );
+}
+
+#[test]
+fn list_models_response() {
+    let response: ListModelsResponse = round_trip("list_models.json");
+    assert_eq!(response.models.len(), 2);
+    assert!(response.models[0].supports("countTokens"));
+    assert_eq!(response.models[1].thinking, Some(true));
+    assert!(response.next_page_token.is_some());
+}
+
+#[test]
+fn batch_embed_contents_response() {
+    let response: BatchEmbedContentsResponse = round_trip("batch_embed_contents.json");
+    assert_eq!(response.embeddings.len(), 2);
+    assert_eq!(response.embeddings[0].values[1], -0.0082);
+}
+
+#[test]
+fn file_response() {
+    let file: File = round_trip("file.json");
+    assert_eq!(file.state, Some(FileState::Active));
+    assert_eq!(file.file_data().unwrap().mime_type, "application/pdf");
+}
+
+#[test]
+fn cached_content_response() {
+    let cache: CachedContent = round_trip("cached_content.json");
+    assert_eq!(cache.request().model, "gemini-2.0-flash-001");
+    assert_eq!(cache.usage_metadata.unwrap().total_token_count, Some(32768));
+}
+
+#[test]
+fn config_sets_modalities_and_thinking() {
+    let req = GenerateContentReq::default()
+        .model("gemini-2.5-flash")
+        .config(
+            GenerateContentConfig::default()
+                .temperature(0.5)
+                .response_modalities(vec![Modality::Text, Modality::Image])
+                .thinking_config(
+                    ThinkingConfig::default()
+                        .include_thoughts(true)
+                        .thinking_budget(1024),
+                ),
+        );
+    let body = serde_json::to_value(&req).unwrap();
+    assert_eq!(
+        body["generationConfig"],
+        serde_json::json!({
+            "temperature": 0.5,
+            "responseModalities": ["TEXT", "IMAGE"],
+            "thinkingConfig": { "includeThoughts": true, "thinkingBudget": 1024 },
+        })
+    );
+}
+
+#[test]
+fn unknown_enum_values_round_trip() {
+    let reason: FinishReason = serde_json::from_str(r#""FINISH_REASON_NEW""#).unwrap();
+    assert_eq!(
+        reason,
+        FinishReason::Unknown("FINISH_REASON_NEW".to_string())
+    );
+    assert_eq!(
+        serde_json::to_string(&reason).unwrap(),
+        r#""FINISH_REASON_NEW""#
+    );
+
+    let known: FinishReason = serde_json::from_str(r#""STOP""#).unwrap();
+    assert_eq!(known, FinishReason::Stop);
+    assert_eq!(serde_json::to_string(&known).unwrap(), r#""STOP""#);
+}